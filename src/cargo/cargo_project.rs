@@ -0,0 +1,272 @@
+use crate::cargo::CargoWorkspace;
+use crate::graph::{Dependency, DependencyType};
+use crate::projects::{resolve_path, Project};
+use anyhow::{bail, Context, Result};
+use log::debug;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// The `Cargo.toml` manifest of a crate or a workspace
+#[derive(Debug, Default, Deserialize)]
+pub(super) struct CargoManifest {
+    pub package: Option<Package>,
+    pub workspace: Option<WorkspaceSection>,
+    #[serde(default)]
+    pub dependencies: BTreeMap<String, DependencySpec>,
+    #[serde(default, rename = "dev-dependencies", alias = "dev_dependencies")]
+    pub dev_dependencies: BTreeMap<String, DependencySpec>,
+    #[serde(default, rename = "build-dependencies", alias = "build_dependencies")]
+    pub build_dependencies: BTreeMap<String, DependencySpec>,
+    /// Platform-specific dependencies, like `[target.'cfg(unix)'.dependencies]`
+    #[serde(default)]
+    pub target: BTreeMap<String, CargoManifest>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct Package {
+    pub name: String,
+}
+
+/// The `[workspace]` section
+#[derive(Debug, Deserialize)]
+pub(super) struct WorkspaceSection {
+    pub members: Option<Vec<String>>,
+    pub exclude: Option<Vec<String>>,
+    /// Dependencies inherited by the members with `workspace = true`
+    #[serde(default)]
+    pub dependencies: BTreeMap<String, DependencySpec>,
+}
+
+/// A dependency, either a version requirement or a table
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub(super) enum DependencySpec {
+    /// `foo = "1.0"`, never a path dependency
+    Version(#[allow(dead_code)] String),
+    Detailed {
+        path: Option<String>,
+        package: Option<String>,
+        workspace: Option<bool>,
+    },
+}
+
+impl CargoManifest {
+    /// Reads the `Cargo.toml` file located in the `project_path` directory
+    pub fn load(workspace_root: &Path, project_path: &str) -> Result<Self> {
+        let path = workspace_root.join(project_path).join("Cargo.toml");
+        debug!("Loading manifest from {:?}", path);
+        let contents =
+            fs::read_to_string(&path).with_context(|| format!("Could not read {:?}", path))?;
+        toml::from_str(&contents).with_context(|| format!("Could not parse {:?}", path))
+    }
+}
+
+/// A struct representing a Rust crate defined by a `Cargo.toml` file
+#[derive(Debug)]
+pub struct CargoProject {
+    /// The name of the crate
+    pub name: String,
+    /// Project's location relative to the root of the workspace
+    pub root: String,
+    /// Path dependencies on other crates of the repository
+    pub dependencies: Vec<Dependency>,
+}
+
+impl CargoProject {
+    /// Checks whether the directory contains a crate manifest (not a virtual workspace one)
+    pub fn is_package(path: &Path) -> bool {
+        let Ok(contents) = fs::read_to_string(path.join("Cargo.toml")) else {
+            return false;
+        };
+        match toml::from_str::<CargoManifest>(&contents) {
+            Ok(manifest) => manifest.package.is_some(),
+            // report the parsing errors when loading the project
+            Err(_) => true,
+        }
+    }
+}
+
+impl Project for CargoProject {
+    fn name(&self) -> Option<&str> {
+        Some(&self.name)
+    }
+
+    fn load(workspace_root: &Path, project_path: &str) -> Result<Self> {
+        let manifest = CargoManifest::load(workspace_root, project_path)?;
+        let Some(package) = &manifest.package else {
+            bail!("'Cargo.toml' has no [package] section");
+        };
+        let cargo_workspace = CargoWorkspace::find(workspace_root, project_path)?;
+
+        let mut dependencies = Vec::new();
+        let sections = std::iter::once(&manifest).chain(manifest.target.values());
+        for section in sections {
+            for (dependencies_table, kind) in [
+                (&section.dependencies, DependencyType::Production),
+                (&section.dev_dependencies, DependencyType::Development),
+                (&section.build_dependencies, DependencyType::Build),
+            ] {
+                for (name, spec) in dependencies_table {
+                    let dependency = match spec {
+                        // `foo = { workspace = true }` uses the `[workspace.dependencies]` entry
+                        DependencySpec::Detailed {
+                            workspace: Some(true),
+                            ..
+                        } => cargo_workspace
+                            .as_ref()
+                            .and_then(|cargo_workspace| cargo_workspace.dependency(name, kind)),
+                        spec => path_dependency(project_path, name, spec, kind),
+                    };
+                    dependencies.extend(dependency);
+                }
+            }
+        }
+
+        Ok(CargoProject {
+            name: package.name.clone(),
+            root: project_path.to_string(),
+            dependencies,
+        })
+    }
+
+    fn dependencies(&self) -> Vec<Dependency> {
+        self.dependencies.clone()
+    }
+}
+
+/// Returns the dependency for `path = "..."` entries, resolved against `manifest_dir`
+pub(super) fn path_dependency(
+    manifest_dir: &str,
+    name: &str,
+    spec: &DependencySpec,
+    kind: DependencyType,
+) -> Option<Dependency> {
+    let DependencySpec::Detailed {
+        path: Some(path),
+        package,
+        ..
+    } = spec
+    else {
+        return None;
+    };
+
+    Some(Dependency {
+        name: package.as_deref().unwrap_or(name).to_string(),
+        path: Some(resolve_path(manifest_dir, path)?),
+        kind,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(root: &Path, path: &str, contents: &str) {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    fn dependencies(project: &CargoProject) -> Vec<(&str, Option<&str>, DependencyType)> {
+        project
+            .dependencies
+            .iter()
+            .map(|dependency| {
+                (
+                    dependency.name.as_str(),
+                    dependency.path.as_deref(),
+                    dependency.kind,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn path_dependencies() {
+        let dir = tempfile::tempdir().unwrap();
+        write(
+            dir.path(),
+            "crates/app/Cargo.toml",
+            r#"
+            [package]
+            name = "app"
+
+            [dependencies]
+            serde = "1.0"
+            core = { path = "../core" }
+            renamed = { path = "../utils", package = "utils" }
+
+            [dev-dependencies]
+            testing = { path = "../testing", version = "0.1" }
+
+            [target.'cfg(unix)'.build-dependencies]
+            build-tools = { path = "../../tools/build" }
+            "#,
+        );
+
+        let project = CargoProject::load(dir.path(), "crates/app").unwrap();
+        assert_eq!(project.name, "app");
+        assert_eq!(
+            dependencies(&project),
+            [
+                ("core", Some("crates/core"), DependencyType::Production),
+                ("utils", Some("crates/utils"), DependencyType::Production),
+                (
+                    "testing",
+                    Some("crates/testing"),
+                    DependencyType::Development
+                ),
+                ("build-tools", Some("tools/build"), DependencyType::Build),
+            ]
+        );
+    }
+
+    #[test]
+    fn workspace_dependencies() {
+        let dir = tempfile::tempdir().unwrap();
+        write(
+            dir.path(),
+            "Cargo.toml",
+            r#"
+            [workspace]
+            members = ["crates/*"]
+
+            [workspace.dependencies]
+            core = { path = "crates/core" }
+            serde = { version = "1.0" }
+            "#,
+        );
+        write(
+            dir.path(),
+            "crates/app/Cargo.toml",
+            r#"
+            [package]
+            name = "app"
+
+            [dependencies]
+            core = { workspace = true }
+            serde = { workspace = true }
+            missing = { workspace = true }
+            "#,
+        );
+
+        let project = CargoProject::load(dir.path(), "crates/app").unwrap();
+        assert_eq!(
+            dependencies(&project),
+            [("core", Some("crates/core"), DependencyType::Production)]
+        );
+    }
+
+    #[test]
+    fn virtual_manifest_is_not_a_package() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "Cargo.toml", "[workspace]\nmembers = []\n");
+        assert!(!CargoProject::is_package(dir.path()));
+        assert!(CargoProject::load(dir.path(), "").is_err());
+
+        write(dir.path(), "Cargo.toml", "[package]\nname = \"root\"\n");
+        assert!(CargoProject::is_package(dir.path()));
+    }
+}
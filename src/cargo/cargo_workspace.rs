@@ -0,0 +1,153 @@
+use crate::cargo::cargo_project::{path_dependency, CargoManifest, DependencySpec};
+use crate::graph::{Dependency, DependencyType};
+use anyhow::Result;
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use log::debug;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Crates declared by the `[workspace]` section of a `Cargo.toml` file
+#[derive(Debug)]
+pub struct CargoWorkspace {
+    /// Workspace location relative to the root of the repository
+    pub root: String,
+    include: GlobSet,
+    exclude: GlobSet,
+    dependencies: BTreeMap<String, DependencySpec>,
+}
+
+impl CargoWorkspace {
+    /// Loads the workspace declared in the `project_path` directory, if any.
+    pub fn load(workspace_root: &Path, project_path: &str) -> Result<Option<Self>> {
+        let manifest = CargoManifest::load(workspace_root, project_path)?;
+        let Some(workspace) = manifest.workspace else {
+            return Ok(None);
+        };
+        debug!(
+            "Cargo workspace '{}' members: {:?}, exclude: {:?}",
+            project_path, workspace.members, workspace.exclude
+        );
+
+        let mut include = GlobSetBuilder::new();
+        for pattern in workspace.members.iter().flatten() {
+            let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+            include.add(GlobBuilder::new(pattern).literal_separator(true).build()?);
+        }
+
+        // excluded paths also exclude everything below them
+        let mut exclude = GlobSetBuilder::new();
+        for pattern in workspace.exclude.iter().flatten() {
+            let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+            exclude.add(GlobBuilder::new(pattern).literal_separator(true).build()?);
+            exclude.add(GlobBuilder::new(&format!("{}/**", pattern)).build()?);
+        }
+
+        Ok(Some(CargoWorkspace {
+            root: project_path.to_string(),
+            include: include.build()?,
+            exclude: exclude.build()?,
+            dependencies: workspace.dependencies,
+        }))
+    }
+
+    /// Finds the closest workspace containing the `project_path` directory, like Cargo does.
+    pub fn find(workspace_root: &Path, project_path: &str) -> Result<Option<Self>> {
+        for dir in Path::new(project_path).ancestors() {
+            if !workspace_root.join(dir).join("Cargo.toml").is_file() {
+                continue;
+            }
+            if let Some(workspace) = Self::load(workspace_root, &dir.to_string_lossy())? {
+                return Ok(Some(workspace));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Checks whether the crate located at `project_path` belongs to this workspace
+    pub fn contains(&self, project_path: &str) -> bool {
+        let Ok(relative_path) = Path::new(project_path).strip_prefix(&self.root) else {
+            return false;
+        };
+        if relative_path.as_os_str().is_empty() {
+            return true;
+        }
+        self.include.is_match(relative_path) && !self.exclude.is_match(relative_path)
+    }
+
+    /// Returns the `[workspace.dependencies]` entry inherited with `workspace = true`
+    pub(super) fn dependency(&self, name: &str, kind: DependencyType) -> Option<Dependency> {
+        let spec = self.dependencies.get(name)?;
+        path_dependency(&self.root, name, spec, kind)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn load(manifest: &str) -> CargoWorkspace {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("rust")).unwrap();
+        fs::write(dir.path().join("rust/Cargo.toml"), manifest).unwrap();
+        CargoWorkspace::load(dir.path(), "rust").unwrap().unwrap()
+    }
+
+    #[test]
+    fn contains_members() {
+        let workspace = load(
+            r#"
+            [workspace]
+            members = ["./crates/*/", "tools/cli"]
+            "#,
+        );
+
+        assert!(workspace.contains("rust"));
+        assert!(workspace.contains("rust/crates/core"));
+        assert!(workspace.contains("rust/tools/cli"));
+        assert!(!workspace.contains("rust/crates/core/nested"));
+        assert!(!workspace.contains("rust/tools/other"));
+        assert!(!workspace.contains("other/crates/core"));
+    }
+
+    #[test]
+    fn excludes_nested_paths() {
+        let workspace = load(
+            r#"
+            [workspace]
+            members = ["crates/**"]
+            exclude = ["crates/legacy"]
+            "#,
+        );
+
+        assert!(workspace.contains("rust/crates/core"));
+        assert!(workspace.contains("rust/crates/legacy-v2"));
+        assert!(!workspace.contains("rust/crates/legacy"));
+        assert!(!workspace.contains("rust/crates/legacy/nested"));
+    }
+
+    #[test]
+    fn finds_the_closest_workspace() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("crates/app")).unwrap();
+        fs::write(
+            dir.path().join("Cargo.toml"),
+            "[workspace]\nmembers = [\"crates/*\"]\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("crates/app/Cargo.toml"),
+            "[package]\nname = \"app\"\n",
+        )
+        .unwrap();
+
+        let workspace = CargoWorkspace::find(dir.path(), "crates/app")
+            .unwrap()
+            .unwrap();
+        assert_eq!(workspace.root, "");
+        assert!(workspace.contains("crates/app"));
+        assert!(CargoWorkspace::load(dir.path(), "crates/app")
+            .unwrap()
+            .is_none());
+    }
+}
@@ -0,0 +1,4 @@
+mod cargo_project;
+mod cargo_workspace;
+pub use cargo_project::CargoProject;
+pub use cargo_workspace::CargoWorkspace;
@@ -0,0 +1,317 @@
+use log::debug;
+use serde::Serialize;
+use serde_with::skip_serializing_none;
+use std::fmt;
+use std::fs;
+
+/// A CI provider detected from the environment
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CiProvider {
+    #[serde(rename = "github-actions")]
+    GitHubActions,
+    #[serde(rename = "gitlab")]
+    GitLab,
+    AzurePipelines,
+    Bitbucket,
+    Jenkins,
+    /// `CI=true` is set, but the provider is unknown
+    Generic,
+}
+
+impl fmt::Display for CiProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            CiProvider::GitHubActions => "GitHub Actions",
+            CiProvider::GitLab => "GitLab CI",
+            CiProvider::AzurePipelines => "Azure Pipelines",
+            CiProvider::Bitbucket => "Bitbucket Pipelines",
+            CiProvider::Jenkins => "Jenkins",
+            CiProvider::Generic => "Generic CI",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// The base and head revisions derived from the CI environment variables
+#[skip_serializing_none]
+#[derive(Debug, Clone, Serialize)]
+pub struct CiEnvironment {
+    pub provider: CiProvider,
+    pub base: Option<String>,
+    pub head: Option<String>,
+}
+
+impl CiEnvironment {
+    /// Detects the CI environment from the process environment variables
+    pub fn detect() -> Option<Self> {
+        Self::from_env(|name| std::env::var(name).ok())
+    }
+
+    /// Returns `true` if the `CI` environment variable is set to `true` or `1`
+    pub fn is_ci() -> bool {
+        std::env::var("CI").is_ok_and(|value| is_truthy(&value))
+    }
+
+    /// Detects the CI environment using the given variable lookup.
+    /// Empty variables are treated as missing.
+    pub fn from_env<F>(lookup: F) -> Option<Self>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let var = |name: &str| lookup(name).filter(|value| !value.is_empty());
+
+        let environment = if var("GITHUB_ACTIONS").is_some() {
+            let event = var("GITHUB_EVENT_PATH").and_then(|path| read_github_event(&path));
+            let base = var("GITHUB_BASE_REF").or_else(|| {
+                event
+                    .as_ref()
+                    .and_then(|event| event["before"].as_str())
+                    .and_then(non_null_sha)
+            });
+            CiEnvironment {
+                provider: CiProvider::GitHubActions,
+                base,
+                head: var("GITHUB_SHA"),
+            }
+        } else if var("GITLAB_CI").is_some() {
+            let base = var("CI_MERGE_REQUEST_DIFF_BASE_SHA")
+                .or_else(|| var("CI_MERGE_REQUEST_TARGET_BRANCH_NAME"))
+                .or_else(|| {
+                    var("CI_COMMIT_BEFORE_SHA")
+                        .as_deref()
+                        .and_then(non_null_sha)
+                });
+            CiEnvironment {
+                provider: CiProvider::GitLab,
+                base,
+                head: var("CI_COMMIT_SHA"),
+            }
+        } else if var("TF_BUILD").is_some() {
+            let base = var("SYSTEM_PULLREQUEST_TARGETBRANCH").map(|branch| {
+                branch
+                    .strip_prefix("refs/heads/")
+                    .map(str::to_string)
+                    .unwrap_or(branch)
+            });
+            CiEnvironment {
+                provider: CiProvider::AzurePipelines,
+                base,
+                head: var("BUILD_SOURCEVERSION"),
+            }
+        } else if var("BITBUCKET_BUILD_NUMBER").is_some() {
+            CiEnvironment {
+                provider: CiProvider::Bitbucket,
+                base: var("BITBUCKET_PR_DESTINATION_BRANCH"),
+                head: var("BITBUCKET_COMMIT"),
+            }
+        } else if var("JENKINS_URL").is_some() {
+            CiEnvironment {
+                provider: CiProvider::Jenkins,
+                base: var("CHANGE_TARGET").or_else(|| var("GIT_PREVIOUS_SUCCESSFUL_COMMIT")),
+                head: var("GIT_COMMIT"),
+            }
+        } else if var("CI").is_some_and(|value| is_truthy(&value)) {
+            CiEnvironment {
+                provider: CiProvider::Generic,
+                base: None,
+                head: None,
+            }
+        } else {
+            return None;
+        };
+
+        debug!("Detected CI environment: {:?}", environment);
+        Some(environment)
+    }
+}
+
+/// Checks whether the `CI` variable value enables the CI mode
+fn is_truthy(value: &str) -> bool {
+    value.eq_ignore_ascii_case("true") || value == "1"
+}
+
+/// Reads the GitHub Actions event payload
+fn read_github_event(path: &str) -> Option<serde_json::Value> {
+    let contents = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+/// Filters out the all-zero SHA used by CI providers for new branches
+fn non_null_sha(sha: &str) -> Option<String> {
+    if sha.chars().all(|c| c == '0') {
+        None
+    } else {
+        Some(sha.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn detect(vars: &[(&str, &str)]) -> Option<CiEnvironment> {
+        let vars: HashMap<_, _> = vars
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        CiEnvironment::from_env(|name| vars.get(name).cloned())
+    }
+
+    fn revisions(environment: &CiEnvironment) -> (Option<&str>, Option<&str>) {
+        (environment.base.as_deref(), environment.head.as_deref())
+    }
+
+    #[test]
+    fn not_ci() {
+        assert!(detect(&[]).is_none());
+        assert!(detect(&[("CI", "")]).is_none());
+        assert!(detect(&[("CI", "false")]).is_none());
+        assert!(detect(&[("CI", "0")]).is_none());
+        assert!(detect(&[("HOME", "/root")]).is_none());
+    }
+
+    #[test]
+    fn generic_ci() {
+        let ci = detect(&[("CI", "true")]).unwrap();
+        assert_eq!(ci.provider, CiProvider::Generic);
+        assert!(detect(&[("CI", "1")]).is_some());
+        assert_eq!(revisions(&ci), (None, None));
+    }
+
+    #[test]
+    fn github_actions_pull_request() {
+        let ci = detect(&[
+            ("CI", "true"),
+            ("GITHUB_ACTIONS", "true"),
+            ("GITHUB_BASE_REF", "main"),
+            ("GITHUB_SHA", "abc123"),
+        ])
+        .unwrap();
+        assert_eq!(ci.provider, CiProvider::GitHubActions);
+        assert_eq!(revisions(&ci), (Some("main"), Some("abc123")));
+    }
+
+    #[test]
+    fn github_actions_push_event() {
+        let path = std::env::temp_dir().join(format!("affected-event-{}.json", std::process::id()));
+        fs::write(&path, r#"{ "before": "def456" }"#).unwrap();
+        let event_path = path.to_string_lossy().to_string();
+
+        let ci = detect(&[
+            ("GITHUB_ACTIONS", "true"),
+            ("GITHUB_BASE_REF", ""),
+            ("GITHUB_EVENT_PATH", &event_path),
+            ("GITHUB_SHA", "abc123"),
+        ])
+        .unwrap();
+        assert_eq!(revisions(&ci), (Some("def456"), Some("abc123")));
+
+        // a new branch has no previous commit
+        fs::write(&path, format!(r#"{{ "before": "{}" }}"#, "0".repeat(40))).unwrap();
+        let ci = detect(&[
+            ("GITHUB_ACTIONS", "true"),
+            ("GITHUB_EVENT_PATH", &event_path),
+        ])
+        .unwrap();
+        assert_eq!(revisions(&ci), (None, None));
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn gitlab_merge_request() {
+        let ci = detect(&[
+            ("GITLAB_CI", "true"),
+            ("CI_MERGE_REQUEST_DIFF_BASE_SHA", "base123"),
+            ("CI_MERGE_REQUEST_TARGET_BRANCH_NAME", "main"),
+            ("CI_COMMIT_SHA", "abc123"),
+        ])
+        .unwrap();
+        assert_eq!(ci.provider, CiProvider::GitLab);
+        assert_eq!(revisions(&ci), (Some("base123"), Some("abc123")));
+
+        let ci = detect(&[
+            ("GITLAB_CI", "true"),
+            ("CI_MERGE_REQUEST_TARGET_BRANCH_NAME", "develop"),
+        ])
+        .unwrap();
+        assert_eq!(revisions(&ci), (Some("develop"), None));
+    }
+
+    #[test]
+    fn gitlab_push() {
+        let ci = detect(&[
+            ("GITLAB_CI", "true"),
+            ("CI_COMMIT_BEFORE_SHA", "before123"),
+            ("CI_COMMIT_SHA", "abc123"),
+        ])
+        .unwrap();
+        assert_eq!(revisions(&ci), (Some("before123"), Some("abc123")));
+
+        let ci = detect(&[
+            ("GITLAB_CI", "true"),
+            ("CI_COMMIT_BEFORE_SHA", &"0".repeat(40)),
+        ])
+        .unwrap();
+        assert_eq!(revisions(&ci), (None, None));
+    }
+
+    #[test]
+    fn azure_pipelines_pull_request() {
+        let ci = detect(&[
+            ("TF_BUILD", "True"),
+            ("SYSTEM_PULLREQUEST_TARGETBRANCH", "refs/heads/main"),
+            ("BUILD_SOURCEVERSION", "abc123"),
+        ])
+        .unwrap();
+        assert_eq!(ci.provider, CiProvider::AzurePipelines);
+        assert_eq!(revisions(&ci), (Some("main"), Some("abc123")));
+
+        let ci = detect(&[
+            ("TF_BUILD", "True"),
+            ("SYSTEM_PULLREQUEST_TARGETBRANCH", "release"),
+        ])
+        .unwrap();
+        assert_eq!(revisions(&ci), (Some("release"), None));
+    }
+
+    #[test]
+    fn bitbucket_pull_request() {
+        let ci = detect(&[
+            ("BITBUCKET_BUILD_NUMBER", "42"),
+            ("BITBUCKET_PR_DESTINATION_BRANCH", "main"),
+            ("BITBUCKET_COMMIT", "abc123"),
+        ])
+        .unwrap();
+        assert_eq!(ci.provider, CiProvider::Bitbucket);
+        assert_eq!(revisions(&ci), (Some("main"), Some("abc123")));
+    }
+
+    #[test]
+    fn jenkins() {
+        let ci = detect(&[
+            ("JENKINS_URL", "https://jenkins.example.com"),
+            ("CHANGE_TARGET", "main"),
+            ("GIT_PREVIOUS_SUCCESSFUL_COMMIT", "previous123"),
+            ("GIT_COMMIT", "abc123"),
+        ])
+        .unwrap();
+        assert_eq!(ci.provider, CiProvider::Jenkins);
+        assert_eq!(revisions(&ci), (Some("main"), Some("abc123")));
+
+        let ci = detect(&[
+            ("JENKINS_URL", "https://jenkins.example.com"),
+            ("GIT_PREVIOUS_SUCCESSFUL_COMMIT", "previous123"),
+        ])
+        .unwrap();
+        assert_eq!(revisions(&ci), (Some("previous123"), None));
+    }
+
+    #[test]
+    fn provider_takes_precedence_over_generic_ci() {
+        let ci = detect(&[("CI", "true"), ("BITBUCKET_BUILD_NUMBER", "1")]).unwrap();
+        assert_eq!(ci.provider, CiProvider::Bitbucket);
+    }
+}
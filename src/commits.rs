@@ -0,0 +1,27 @@
+use serde::Serialize;
+
+/// A commit between the base and the head, along with the affected files and projects it touched
+#[derive(Debug, Clone, Serialize)]
+pub struct AffectedCommit {
+    /// The full SHA of the commit
+    pub sha: String,
+    /// The author name
+    pub author: String,
+    /// The author email
+    pub email: String,
+    /// The commit time as seconds since the Unix epoch
+    pub time: i64,
+    /// The first line of the commit message
+    pub summary: String,
+    /// The affected files touched by the commit
+    pub files: Vec<String>,
+    /// The projects owning the affected files touched by the commit
+    pub projects: Vec<String>,
+}
+
+impl AffectedCommit {
+    /// Returns the abbreviated SHA of the commit
+    pub fn short_sha(&self) -> &str {
+        &self.sha[..self.sha.len().min(7)]
+    }
+}
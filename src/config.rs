@@ -1,7 +1,9 @@
-use anyhow::Result;
+use anyhow::{bail, Result};
+use clap::ValueEnum;
 use globset::Glob;
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
+use std::collections::BTreeMap;
 use std::fs::File;
 use std::io::{BufReader, Write};
 use std::path::PathBuf;
@@ -10,6 +12,26 @@ use std::path::PathBuf;
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Config {
     pub base: Option<String>,
+    pub head: Option<String>,
+    pub merge_base: Option<bool>,
+    pub remote: Option<String>,
+    pub fetch: Option<bool>,
+    pub untracked: Option<bool>,
+    pub similarity: Option<u16>,
+    pub mode: Option<DiffMode>,
+    pub ci: Option<bool>,
+    pub submodules: Option<bool>,
+    pub since_tag: Option<String>,
+    pub since: Option<String>,
+    /// Only count the changes matching the Nx named input (e.g. `production`)
+    pub named_input: Option<String>,
+    /// Only count the changes matching the inputs of the Nx target (e.g. `build`)
+    pub target: Option<String>,
+    /// Inputs of the projects by name or glob pattern, overriding the Nx ones
+    pub inputs: Option<BTreeMap<String, Vec<String>>>,
+    /// Files affecting all the projects when changed, like `tsconfig.base.json`
+    #[serde(alias = "globalDependencies")]
+    pub global_dependencies: Option<Vec<GlobalDependency>>,
     pub tasks: Option<Vec<Task>>,
 }
 
@@ -38,6 +60,21 @@ impl Default for Config {
     fn default() -> Self {
         Config {
             base: Some("main".to_string()),
+            head: None,
+            merge_base: None,
+            remote: None,
+            fetch: None,
+            untracked: None,
+            similarity: None,
+            mode: None,
+            ci: None,
+            submodules: None,
+            since_tag: None,
+            since: None,
+            named_input: None,
+            target: None,
+            inputs: None,
+            global_dependencies: None,
             tasks: Some(vec![
                 Task {
                     name: "eslint".to_string(),
@@ -65,6 +102,51 @@ impl Default for Config {
     }
 }
 
+/// Selects which changes are compared against the base
+#[derive(ValueEnum, Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffMode {
+    /// Changes staged in the index
+    Staged,
+    /// Changes in the working directory that are not staged
+    Unstaged,
+    /// Committed changes up to HEAD
+    Committed,
+    /// Committed, staged and unstaged changes
+    #[default]
+    All,
+}
+
+/// A glob pattern of the files affecting all the projects,
+/// or only the projects matching the name or `tag:` glob patterns
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum GlobalDependency {
+    Pattern(String),
+    Scoped {
+        pattern: String,
+        projects: Vec<String>,
+    },
+}
+
+impl GlobalDependency {
+    pub fn pattern(&self) -> &str {
+        match self {
+            GlobalDependency::Pattern(pattern) | GlobalDependency::Scoped { pattern, .. } => {
+                pattern
+            }
+        }
+    }
+
+    /// Name or `tag:` glob patterns of the affected projects, `None` for all the projects
+    pub fn projects(&self) -> Option<&Vec<String>> {
+        match self {
+            GlobalDependency::Pattern(_) => None,
+            GlobalDependency::Scoped { projects, .. } => Some(projects),
+        }
+    }
+}
+
 #[skip_serializing_none]
 #[derive(Debug, Default, Serialize, Deserialize, Clone)]
 pub struct Task {
@@ -72,6 +154,8 @@ pub struct Task {
     pub description: Option<String>,
     pub patterns: Option<Vec<String>>,
     pub separator: Option<String>,
+    pub skip_deleted: Option<bool>,
+    pub mode: Option<DiffMode>,
     pub commands: Vec<String>,
 }
 
@@ -98,6 +182,13 @@ impl Config {
         let file = File::open(file_path)?;
         let reader = BufReader::new(file);
         let dictionary: Self = serde_yaml::from_reader(reader)?;
+        if let Some(similarity) = dictionary.similarity.filter(|similarity| *similarity > 100) {
+            bail!(
+                "The similarity must be between 0 and 100, got {} in {:?}",
+                similarity,
+                file_path
+            );
+        }
 
         Ok(dictionary)
     }
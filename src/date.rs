@@ -0,0 +1,191 @@
+use anyhow::{bail, Context, Result};
+
+/// Parses a date into seconds since the Unix epoch.
+///
+/// Supported formats:
+/// - `2024-01-31` (midnight UTC)
+/// - `2024-01-31T10:30:00Z`, `2024-01-31 10:30:00` (UTC)
+/// - `@1706700000` (Unix timestamp)
+/// - `7d`, `2w`, `12h`, `7 days ago`, `2 weeks ago` (relative to `now`)
+pub fn parse_timestamp(value: &str, now: i64) -> Result<i64> {
+    let value = value.trim();
+
+    if let Some(seconds) = value.strip_prefix('@') {
+        return seconds
+            .parse()
+            .with_context(|| format!("Invalid timestamp '{}'", value));
+    }
+
+    if let Some(seconds) = parse_relative(value) {
+        return Ok(now - seconds);
+    }
+
+    parse_datetime(value).with_context(|| {
+        format!(
+            "Invalid date '{}', expected YYYY-MM-DD, YYYY-MM-DDTHH:MM:SS, @<timestamp> or a relative date like '7 days ago'",
+            value
+        )
+    })
+}
+
+/// Parses relative dates like `7d` or `7 days ago` into a number of seconds
+fn parse_relative(value: &str) -> Option<i64> {
+    let value = value.strip_suffix("ago").unwrap_or(value).trim();
+    let split = value.find(|c: char| !c.is_ascii_digit())?;
+    let (amount, unit) = value.split_at(split);
+    let amount: i64 = amount.parse().ok()?;
+
+    let unit_seconds = match unit.trim().trim_end_matches('s') {
+        "m" | "min" | "minute" => 60,
+        "h" | "hour" => 60 * 60,
+        "d" | "day" => 24 * 60 * 60,
+        "w" | "week" => 7 * 24 * 60 * 60,
+        _ => return None,
+    };
+
+    Some(amount * unit_seconds)
+}
+
+/// Parses `YYYY-MM-DD` with an optional `THH:MM[:SS][Z]` time, in UTC
+fn parse_datetime(value: &str) -> Result<i64> {
+    let (date, time) = match value.split_once(['T', ' ']) {
+        Some((date, time)) => (date, Some(time.trim_end_matches('Z'))),
+        None => (value, None),
+    };
+
+    let mut date_parts = date.splitn(3, '-').map(str::parse::<i64>);
+    let (Some(Ok(year)), Some(Ok(month)), Some(Ok(day))) =
+        (date_parts.next(), date_parts.next(), date_parts.next())
+    else {
+        bail!("Invalid date");
+    };
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        bail!("Invalid date");
+    }
+
+    let mut seconds = days_from_civil(year, month, day) * 24 * 60 * 60;
+
+    if let Some(time) = time {
+        let mut time_parts = time.splitn(3, ':').map(str::parse::<i64>);
+        let hours = time_parts.next().transpose()?.unwrap_or(0);
+        let minutes = time_parts.next().transpose()?.unwrap_or(0);
+        let secs = time_parts.next().transpose()?.unwrap_or(0);
+        seconds += hours * 60 * 60 + minutes * 60 + secs;
+    }
+
+    Ok(seconds)
+}
+
+/// Returns the number of days since the Unix epoch for a proleptic Gregorian date
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month_index = (month + 9) % 12;
+    let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = 24 * 60 * 60;
+    /// 2024-01-31T00:00:00Z
+    const NOW: i64 = 1_706_659_200;
+
+    #[test]
+    fn parses_relative_dates() {
+        assert_eq!(parse_timestamp("7d", NOW).unwrap(), NOW - 7 * DAY);
+        assert_eq!(parse_timestamp("7 days ago", NOW).unwrap(), NOW - 7 * DAY);
+        assert_eq!(parse_timestamp("1 day ago", NOW).unwrap(), NOW - DAY);
+        assert_eq!(parse_timestamp("2w", NOW).unwrap(), NOW - 14 * DAY);
+        assert_eq!(parse_timestamp("2 weeks ago", NOW).unwrap(), NOW - 14 * DAY);
+        assert_eq!(parse_timestamp("12h", NOW).unwrap(), NOW - 12 * 60 * 60);
+        assert_eq!(
+            parse_timestamp("30 minutes ago", NOW).unwrap(),
+            NOW - 30 * 60
+        );
+        assert_eq!(parse_timestamp(" 3d ", NOW).unwrap(), NOW - 3 * DAY);
+    }
+
+    #[test]
+    fn parses_dates() {
+        assert_eq!(parse_timestamp("1970-01-01", NOW).unwrap(), 0);
+        assert_eq!(parse_timestamp("2024-01-31", NOW).unwrap(), NOW);
+        assert_eq!(parse_timestamp("1969-12-31", NOW).unwrap(), -DAY);
+    }
+
+    #[test]
+    fn parses_dates_with_time() {
+        let expected = NOW + 10 * 60 * 60 + 30 * 60;
+        assert_eq!(
+            parse_timestamp("2024-01-31T10:30:00Z", NOW).unwrap(),
+            expected
+        );
+        assert_eq!(
+            parse_timestamp("2024-01-31T10:30:00", NOW).unwrap(),
+            expected
+        );
+        assert_eq!(
+            parse_timestamp("2024-01-31 10:30:00", NOW).unwrap(),
+            expected
+        );
+        assert_eq!(parse_timestamp("2024-01-31T10:30", NOW).unwrap(), expected);
+        assert_eq!(
+            parse_timestamp("2024-01-31T10:30:15Z", NOW).unwrap(),
+            expected + 15
+        );
+    }
+
+    #[test]
+    fn parses_unix_timestamps() {
+        assert_eq!(parse_timestamp("@1706700000", NOW).unwrap(), 1_706_700_000);
+        assert!(parse_timestamp("@yesterday", NOW).is_err());
+    }
+
+    #[test]
+    fn handles_leap_years() {
+        assert_eq!(
+            parse_timestamp("2024-03-01", NOW).unwrap()
+                - parse_timestamp("2024-02-28", NOW).unwrap(),
+            2 * DAY
+        );
+        assert_eq!(
+            parse_timestamp("2023-03-01", NOW).unwrap()
+                - parse_timestamp("2023-02-28", NOW).unwrap(),
+            DAY
+        );
+        // divisible by 100 but not by 400
+        assert_eq!(
+            parse_timestamp("1900-03-01", NOW).unwrap()
+                - parse_timestamp("1900-02-28", NOW).unwrap(),
+            DAY
+        );
+        assert_eq!(
+            parse_timestamp("2000-03-01", NOW).unwrap()
+                - parse_timestamp("2000-02-28", NOW).unwrap(),
+            2 * DAY
+        );
+        assert_eq!(parse_timestamp("2000-02-29", NOW).unwrap(), 951_782_400);
+    }
+
+    #[test]
+    fn rejects_invalid_dates() {
+        for value in [
+            "",
+            "yesterday",
+            "7 fortnights ago",
+            "2024",
+            "2024-01",
+            "2024-13-01",
+            "2024-00-10",
+            "2024-01-32",
+            "2024-01-31Tnoon",
+            "31/01/2024",
+        ] {
+            assert!(parse_timestamp(value, NOW).is_err(), "{}", value);
+        }
+    }
+}
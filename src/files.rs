@@ -0,0 +1,88 @@
+use git2::Delta;
+use serde::Serialize;
+use serde_with::skip_serializing_none;
+use std::fmt;
+
+/// The kind of change applied to an affected file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileStatus {
+    Added,
+    Modified,
+    Deleted,
+    Renamed,
+    Copied,
+    TypeChange,
+}
+
+impl FileStatus {
+    /// Maps a git delta to a file status.
+    /// Untracked files are reported as added, and conflicts as modified.
+    pub fn from_delta(delta: Delta) -> Option<Self> {
+        match delta {
+            Delta::Added | Delta::Untracked => Some(FileStatus::Added),
+            Delta::Modified | Delta::Conflicted => Some(FileStatus::Modified),
+            Delta::Deleted => Some(FileStatus::Deleted),
+            Delta::Renamed => Some(FileStatus::Renamed),
+            Delta::Copied => Some(FileStatus::Copied),
+            Delta::Typechange => Some(FileStatus::TypeChange),
+            Delta::Unmodified | Delta::Ignored | Delta::Unreadable => None,
+        }
+    }
+}
+
+impl fmt::Display for FileStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            FileStatus::Added => "added",
+            FileStatus::Modified => "modified",
+            FileStatus::Deleted => "deleted",
+            FileStatus::Renamed => "renamed",
+            FileStatus::Copied => "copied",
+            FileStatus::TypeChange => "typechange",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A file changed between the base and the head
+#[skip_serializing_none]
+#[derive(Debug, Clone, Serialize)]
+pub struct AffectedFile {
+    /// The path of the file relative to the root of the workspace
+    pub path: String,
+    /// The previous path of the file, for renamed and copied files
+    pub old_path: Option<String>,
+    /// The kind of change
+    pub status: FileStatus,
+    /// The number of added lines
+    pub additions: usize,
+    /// The number of removed lines
+    pub deletions: usize,
+    /// The changed line ranges, empty for binary files and submodules
+    pub hunks: Vec<Hunk>,
+}
+
+impl AffectedFile {
+    /// Returns the line ranges of the new file touched by the changes, as `start-end` strings
+    pub fn changed_lines(&self) -> Vec<String> {
+        self.hunks
+            .iter()
+            .filter(|hunk| hunk.new_lines > 0)
+            .map(|hunk| format!("{}-{}", hunk.new_start, hunk.new_start + hunk.new_lines - 1))
+            .collect()
+    }
+}
+
+/// A contiguous block of changed lines, similar to the `@@ -a,b +c,d @@` header of a unified diff
+#[derive(Debug, Clone, Serialize)]
+pub struct Hunk {
+    /// The first line of the range in the old file
+    pub old_start: u32,
+    /// The number of lines of the range in the old file
+    pub old_lines: u32,
+    /// The first line of the range in the new file
+    pub new_start: u32,
+    /// The number of lines of the range in the new file
+    pub new_lines: u32,
+}
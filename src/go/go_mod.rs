@@ -0,0 +1,163 @@
+use anyhow::{Context, Result};
+use log::debug;
+use std::fs;
+use std::path::Path;
+
+/// The `go.mod` file of a Go module
+#[derive(Debug, Default)]
+pub(super) struct GoMod {
+    /// The module path
+    pub module: Option<String>,
+    /// `replace` directives pointing to local directories, as `(module path, directory)` pairs
+    pub local_replacements: Vec<(String, String)>,
+}
+
+impl GoMod {
+    /// Reads the `go.mod` file located in the `project_path` directory
+    pub fn load(workspace_root: &Path, project_path: &str) -> Result<Self> {
+        let path = workspace_root.join(project_path).join("go.mod");
+        debug!("Loading module from {:?}", path);
+        let contents =
+            fs::read_to_string(&path).with_context(|| format!("Could not read {:?}", path))?;
+        Ok(Self::parse(&contents))
+    }
+
+    pub fn parse(contents: &str) -> Self {
+        let module = directives(contents, "module")
+            .into_iter()
+            .next()
+            .map(|module| unquote(&module).to_string());
+
+        // replace golang.org/x/net v1.2.3 => ./fork/net
+        let local_replacements = directives(contents, "replace")
+            .iter()
+            .filter_map(|directive| {
+                let (old, new) = directive.split_once("=>")?;
+                let old = unquote(old.split_whitespace().next()?);
+                let new = unquote(new.split_whitespace().next()?);
+                is_local_path(new).then(|| (old.to_string(), new.to_string()))
+            })
+            .collect();
+
+        GoMod {
+            module,
+            local_replacements,
+        }
+    }
+}
+
+/// Returns the arguments of the `keyword` directives,
+/// both in the single line (`use ./a`) and in the block (`use ( ./a ./b )`) forms
+pub(super) fn directives(contents: &str, keyword: &str) -> Vec<String> {
+    let mut result = Vec::new();
+    let mut in_block = false;
+
+    for line in contents.lines() {
+        let line = line.split("//").next().unwrap_or_default().trim();
+        if in_block {
+            if line == ")" {
+                in_block = false;
+            } else if !line.is_empty() {
+                result.push(line.to_string());
+            }
+            continue;
+        }
+
+        let Some(rest) = line.strip_prefix(keyword) else {
+            continue;
+        };
+        if !rest.starts_with([' ', '\t', '(']) {
+            continue;
+        }
+        match rest.trim() {
+            "(" => in_block = true,
+            rest => result.push(rest.to_string()),
+        }
+    }
+
+    result
+}
+
+/// Local paths start with `./` or `../`, like in the Go toolchain
+pub(super) fn is_local_path(path: &str) -> bool {
+    path == "." || path == ".." || path.starts_with("./") || path.starts_with("../")
+}
+
+pub(super) fn unquote(value: &str) -> &str {
+    value.trim().trim_matches(|c| c == '"' || c == '`')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GO_MOD: &str = r#"module example.com/acme/api // the API service
+
+go 1.22
+
+require (
+	example.com/acme/shared v0.0.0
+	golang.org/x/net v0.20.0 // indirect
+)
+
+replace example.com/acme/shared => ../shared
+
+replace (
+	golang.org/x/net v0.20.0 => ./third_party/net
+	golang.org/x/text => golang.org/x/text v0.14.0
+)
+"#;
+
+    #[test]
+    fn parses_module_and_local_replacements() {
+        let go_mod = GoMod::parse(GO_MOD);
+
+        assert_eq!(go_mod.module.as_deref(), Some("example.com/acme/api"));
+        assert_eq!(
+            go_mod.local_replacements,
+            [
+                (
+                    "example.com/acme/shared".to_string(),
+                    "../shared".to_string()
+                ),
+                (
+                    "golang.org/x/net".to_string(),
+                    "./third_party/net".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_directives_in_both_forms() {
+        assert_eq!(directives(GO_MOD, "go"), ["1.22"]);
+        assert_eq!(
+            directives(GO_MOD, "require"),
+            ["example.com/acme/shared v0.0.0", "golang.org/x/net v0.20.0"]
+        );
+        assert_eq!(
+            directives(
+                "use ./a\nuse (\n\t./b\n\n\t\"./c\"\n)\nuseless ./d\n",
+                "use"
+            ),
+            ["./a", "./b", "\"./c\""]
+        );
+        assert!(directives(GO_MOD, "toolchain").is_empty());
+    }
+
+    #[test]
+    fn parses_quoted_module_paths() {
+        let go_mod = GoMod::parse("module \"example.com/quoted\"\n");
+        assert_eq!(go_mod.module.as_deref(), Some("example.com/quoted"));
+        assert!(GoMod::parse("go 1.22\n").module.is_none());
+    }
+
+    #[test]
+    fn detects_local_paths() {
+        assert!(is_local_path("."));
+        assert!(is_local_path("./shared"));
+        assert!(is_local_path("../shared"));
+        assert!(!is_local_path("example.com/acme/shared"));
+        assert!(!is_local_path("/abs/shared"));
+    }
+}
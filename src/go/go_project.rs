@@ -0,0 +1,237 @@
+use crate::go::go_mod::GoMod;
+use crate::go::GoWorkspace;
+use crate::graph::{Dependency, DependencyType};
+use crate::projects::{resolve_path, Project};
+use anyhow::{Context, Result};
+use ignore::WalkBuilder;
+use log::{debug, warn};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// A struct representing a Go module defined by a `go.mod` file
+#[derive(Debug)]
+pub struct GoProject {
+    /// The module path
+    pub name: String,
+    /// Project's location relative to the root of the workspace
+    pub root: String,
+    /// Local `replace` targets and the modules of the repository imported by the Go files
+    pub dependencies: Vec<Dependency>,
+}
+
+impl Project for GoProject {
+    fn name(&self) -> Option<&str> {
+        Some(&self.name)
+    }
+
+    fn load(workspace_root: &Path, project_path: &str) -> Result<Self> {
+        let go_mod = GoMod::load(workspace_root, project_path)?;
+        let name = go_mod
+            .module
+            .with_context(|| format!("'{}/go.mod' has no module directive", project_path))?;
+
+        // module paths of the repository and their directories
+        let mut local_modules = Vec::new();
+        if let Some(go_workspace) = GoWorkspace::find(workspace_root, project_path)? {
+            for module_dir in &go_workspace.modules {
+                match GoMod::load(workspace_root, module_dir) {
+                    Ok(GoMod {
+                        module: Some(module),
+                        ..
+                    }) => local_modules.push((module, module_dir.clone())),
+                    Ok(_) => {}
+                    Err(err) => warn!("Skipping Go module '{}': {:#}", module_dir, err),
+                }
+            }
+        }
+
+        let mut dependencies = BTreeMap::new();
+        for (module, path) in &go_mod.local_replacements {
+            if let Some(module_dir) = resolve_path(project_path, path) {
+                dependencies.insert(
+                    module_dir.clone(),
+                    (module.clone(), DependencyType::Production),
+                );
+                // replacements take precedence over the workspace modules
+                local_modules.insert(0, (module.clone(), module_dir));
+            }
+        }
+
+        for (import, kind) in find_imports(&workspace_root.join(project_path)) {
+            // the longest module path wins, like in the Go toolchain
+            let Some((module, module_dir)) = local_modules
+                .iter()
+                .filter(|(module, _)| {
+                    import == *module || import.starts_with(&format!("{}/", module))
+                })
+                .max_by_key(|(module, _)| module.len())
+            else {
+                continue;
+            };
+            let entry = dependencies
+                .entry(module_dir.clone())
+                .or_insert((module.clone(), kind));
+            if kind == DependencyType::Production {
+                entry.1 = kind;
+            }
+        }
+
+        Ok(GoProject {
+            name,
+            root: project_path.to_string(),
+            dependencies: dependencies
+                .into_iter()
+                .map(|(path, (name, kind))| Dependency {
+                    name,
+                    path: Some(path),
+                    kind,
+                })
+                .collect(),
+        })
+    }
+
+    fn dependencies(&self) -> Vec<Dependency> {
+        self.dependencies.clone()
+    }
+}
+
+/// Returns the import paths of the Go files of the module,
+/// imports of the `_test.go` files are development dependencies
+fn find_imports(module_dir: &Path) -> Vec<(String, DependencyType)> {
+    let walker = WalkBuilder::new(module_dir)
+        .standard_filters(true)
+        .filter_entry(|entry| {
+            if entry.depth() == 0 || !entry.path().is_dir() {
+                return true;
+            }
+            // the Go toolchain ignores these directories, nested modules are separate projects
+            let name = entry.file_name().to_string_lossy();
+            !(name.starts_with('.')
+                || name.starts_with('_')
+                || name == "vendor"
+                || name == "testdata"
+                || entry.path().join("go.mod").is_file())
+        })
+        .build();
+
+    let mut imports = Vec::new();
+    for entry in walker.flatten() {
+        let path = entry.path();
+        if path.extension().is_none_or(|extension| extension != "go") {
+            continue;
+        }
+        let Ok(contents) = fs::read_to_string(path) else {
+            debug!("Could not read {:?}", path);
+            continue;
+        };
+        let kind = if path.to_string_lossy().ends_with("_test.go") {
+            DependencyType::Development
+        } else {
+            DependencyType::Production
+        };
+        imports.extend(
+            parse_imports(&contents)
+                .into_iter()
+                .map(|import| (import, kind)),
+        );
+    }
+    imports
+}
+
+/// Parses the `import "path"`, `import alias "path"` and `import ( ... )` declarations
+fn parse_imports(contents: &str) -> Vec<String> {
+    let mut imports = Vec::new();
+    let mut in_block = false;
+
+    for line in contents.lines() {
+        let line = line.split("//").next().unwrap_or_default().trim();
+        if in_block {
+            if line.starts_with(')') {
+                in_block = false;
+            } else {
+                imports.extend(string_literal(line));
+            }
+            continue;
+        }
+
+        // imports always precede the other declarations
+        if ["func ", "type ", "var ", "const "]
+            .iter()
+            .any(|keyword| line.starts_with(keyword))
+        {
+            break;
+        }
+        if let Some(rest) = line.strip_prefix("import") {
+            match rest.trim() {
+                "(" => in_block = true,
+                rest => imports.extend(string_literal(rest)),
+            }
+        }
+    }
+
+    imports
+}
+
+/// Returns the content of the first `"..."` or `` `...` `` literal of the line
+fn string_literal(line: &str) -> Option<String> {
+    let start = line.find(['"', '`'])?;
+    let quote = line[start..].chars().next()?;
+    let rest = &line[start + 1..];
+    let end = rest.find(quote)?;
+    Some(rest[..end].to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_single_and_grouped_imports() {
+        let imports = parse_imports(
+            r#"// Package api serves the API.
+package api
+
+import "fmt"
+import shared "example.com/acme/shared"
+
+import (
+	"net/http" // the standard library
+
+	// commented out "example.com/acme/unused"
+	_ "example.com/acme/plugins"
+	. `example.com/acme/dot`
+)
+
+func main() {
+	fmt.Println("import \"example.com/acme/ignored\"")
+}
+"#,
+        );
+
+        assert_eq!(
+            imports,
+            [
+                "fmt",
+                "example.com/acme/shared",
+                "net/http",
+                "example.com/acme/plugins",
+                "example.com/acme/dot",
+            ]
+        );
+    }
+
+    #[test]
+    fn stops_at_the_first_declaration() {
+        let imports = parse_imports("package api\n\nvar x = 1\n\nimport \"fmt\"\n");
+        assert!(imports.is_empty());
+    }
+
+    #[test]
+    fn extracts_string_literals() {
+        assert_eq!(string_literal(r#"alias "a/b""#).as_deref(), Some("a/b"));
+        assert_eq!(string_literal("`a/b`").as_deref(), Some("a/b"));
+        assert_eq!(string_literal("\"unterminated"), None);
+        assert_eq!(string_literal("none"), None);
+    }
+}
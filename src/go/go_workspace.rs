@@ -0,0 +1,51 @@
+use crate::go::go_mod::{directives, unquote};
+use crate::projects::resolve_path;
+use anyhow::{Context, Result};
+use log::debug;
+use std::fs;
+use std::path::Path;
+
+/// Modules declared by the `use` directives of a `go.work` file
+#[derive(Debug)]
+pub struct GoWorkspace {
+    /// Workspace location relative to the root of the repository
+    pub root: String,
+    /// Module directories relative to the root of the repository
+    pub modules: Vec<String>,
+}
+
+impl GoWorkspace {
+    /// Loads the `go.work` file located in the `project_path` directory
+    pub fn load(workspace_root: &Path, project_path: &str) -> Result<Self> {
+        let path = workspace_root.join(project_path).join("go.work");
+        debug!("Loading Go workspace from {:?}", path);
+        let contents =
+            fs::read_to_string(&path).with_context(|| format!("Could not read {:?}", path))?;
+
+        let modules = directives(&contents, "use")
+            .iter()
+            .filter_map(|module| resolve_path(project_path, unquote(module)))
+            .collect();
+        debug!("Go workspace modules: {:?}", modules);
+
+        Ok(GoWorkspace {
+            root: project_path.to_string(),
+            modules,
+        })
+    }
+
+    /// Finds the closest `go.work` file containing the `project_path` directory, like the Go toolchain does.
+    pub fn find(workspace_root: &Path, project_path: &str) -> Result<Option<Self>> {
+        for dir in Path::new(project_path).ancestors() {
+            if workspace_root.join(dir).join("go.work").is_file() {
+                return Self::load(workspace_root, &dir.to_string_lossy()).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Checks whether the module located at `project_path` is used by the workspace
+    pub fn contains(&self, project_path: &str) -> bool {
+        self.modules.iter().any(|module| module == project_path)
+    }
+}
@@ -0,0 +1,5 @@
+mod go_mod;
+mod go_project;
+mod go_workspace;
+pub use go_project::GoProject;
+pub use go_workspace::GoWorkspace;
@@ -0,0 +1,169 @@
+use crate::gradle::gradle_settings::{string_literals, strip_comments, SETTINGS_FILES};
+use crate::gradle::GradleSettings;
+use crate::graph::{Dependency, DependencyType};
+use crate::projects::Project;
+use anyhow::{Context, Result};
+use log::debug;
+use std::fs;
+use std::path::Path;
+
+/// The build file names, Kotlin DSL first
+const BUILD_FILES: [&str; 2] = ["build.gradle.kts", "build.gradle"];
+
+/// A struct representing a Gradle project defined by a `build.gradle(.kts)` file
+#[derive(Debug)]
+pub struct GradleProject {
+    /// The Gradle path of the project (like `:services:api`), or `rootProject.name` for the root project
+    pub name: Option<String>,
+    /// Project's location relative to the root of the workspace
+    pub root: String,
+    /// `project(":x")` references to other projects of the build
+    pub dependencies: Vec<Dependency>,
+}
+
+impl GradleProject {
+    /// Checks whether the directory contains a build or a settings file
+    pub fn is_project(path: &Path) -> bool {
+        BUILD_FILES
+            .iter()
+            .chain(SETTINGS_FILES.iter())
+            .any(|name| path.join(name).is_file())
+    }
+
+    /// Loads the project, resolving the `project(":x")` references against the settings of the build
+    pub fn load_with_settings(
+        workspace_root: &Path,
+        project_path: &str,
+        settings: Option<&GradleSettings>,
+    ) -> Result<Self> {
+        let Some(settings) = settings else {
+            // a single-project build
+            return Ok(GradleProject {
+                name: None,
+                root: project_path.to_string(),
+                dependencies: Vec::new(),
+            });
+        };
+        let gradle_path = settings
+            .gradle_path(project_path)
+            .with_context(|| format!("'{}' is not included in the Gradle settings", project_path))?
+            .to_string();
+        let name = match gradle_path.as_str() {
+            ":" => settings.root_name.clone(),
+            gradle_path => Some(gradle_path.to_string()),
+        };
+
+        let dir = workspace_root.join(project_path);
+        let mut dependencies = Vec::new();
+        if let Some(path) = BUILD_FILES
+            .iter()
+            .map(|name| dir.join(name))
+            .find(|path| path.is_file())
+        {
+            debug!("Loading Gradle build from {:?}", path);
+            let contents =
+                fs::read_to_string(&path).with_context(|| format!("Could not read {:?}", path))?;
+
+            for (reference, configuration) in project_references(&strip_comments(&contents)) {
+                // `project("x")` is relative to the current project
+                let reference = match reference.strip_prefix(':') {
+                    Some(_) => reference,
+                    None => format!("{}:{}", gradle_path.trim_end_matches(':'), reference),
+                };
+                let Some(dependency_dir) = settings.project_dir(&reference) else {
+                    continue;
+                };
+                dependencies.push(Dependency {
+                    name: reference.clone(),
+                    path: Some(dependency_dir.to_string()),
+                    kind: if configuration.starts_with("test") {
+                        DependencyType::Development
+                    } else {
+                        DependencyType::Production
+                    },
+                });
+            }
+        }
+
+        Ok(GradleProject {
+            name,
+            root: project_path.to_string(),
+            dependencies,
+        })
+    }
+}
+
+impl Project for GradleProject {
+    fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    fn load(workspace_root: &Path, project_path: &str) -> Result<Self> {
+        let settings = GradleSettings::find(workspace_root, project_path)?;
+        Self::load_with_settings(workspace_root, project_path, settings.as_ref())
+    }
+
+    fn dependencies(&self) -> Vec<Dependency> {
+        self.dependencies.clone()
+    }
+}
+
+/// Returns the `project(":x")` and `project(path: ":x")` references,
+/// along with the configuration starting the line (like `testImplementation`)
+fn project_references(contents: &str) -> Vec<(String, String)> {
+    let mut result = Vec::new();
+
+    for line in contents.lines() {
+        let line = line.trim();
+        let configuration: String = line
+            .chars()
+            .take_while(|c| c.is_alphanumeric() || *c == '_')
+            .collect();
+
+        let mut rest = line;
+        while let Some(index) = rest.find("project(") {
+            let preceded_by_identifier = rest[..index]
+                .chars()
+                .next_back()
+                .is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '.');
+            rest = &rest[index + "project(".len()..];
+            if preceded_by_identifier {
+                continue;
+            }
+            let arguments = rest.split(')').next().unwrap_or_default();
+            if let Some(reference) = string_literals(arguments).into_iter().next() {
+                result.push((reference, configuration.clone()));
+            }
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_project_references() {
+        let references = project_references(
+            r#"
+dependencies {
+    implementation(project(":libs:core"))
+    api project(path: ':libs:model', configuration: 'default')
+    testImplementation(project(":testing"))
+    implementation(subproject("ignored"))
+}
+"#,
+        );
+
+        assert_eq!(
+            references,
+            [
+                (":libs:core".to_string(), "implementation".to_string()),
+                (":libs:model".to_string(), "api".to_string()),
+                (":testing".to_string(), "testImplementation".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,287 @@
+use crate::projects::resolve_path;
+use anyhow::{Context, Result};
+use log::debug;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The settings file names, Kotlin DSL first
+pub(super) const SETTINGS_FILES: [&str; 2] = ["settings.gradle.kts", "settings.gradle"];
+
+/// Projects included by a `settings.gradle(.kts)` file, parsed statically
+#[derive(Debug)]
+pub struct GradleSettings {
+    /// Build location relative to the root of the repository
+    pub root: String,
+    /// The `rootProject.name` value
+    pub root_name: Option<String>,
+    /// Gradle paths (like `:services:api`) and directories of the included projects
+    projects: Vec<(String, String)>,
+}
+
+impl GradleSettings {
+    /// Loads the settings file located in the `project_path` directory
+    pub fn load(workspace_root: &Path, project_path: &str) -> Result<Self> {
+        let dir = workspace_root.join(project_path);
+        let path = SETTINGS_FILES
+            .iter()
+            .map(|name| dir.join(name))
+            .find(|path| path.is_file())
+            .with_context(|| format!("Could not find the settings file in {:?}", dir))?;
+        debug!("Loading Gradle settings from {:?}", path);
+        let contents =
+            fs::read_to_string(&path).with_context(|| format!("Could not read {:?}", path))?;
+        Ok(Self::parse(project_path, &contents))
+    }
+
+    /// Parses the settings file of the build located in the `project_path` directory
+    pub fn parse(project_path: &str, contents: &str) -> Self {
+        let contents = strip_comments(contents);
+
+        let mut root_name = None;
+        let mut projects: Vec<(String, String)> = Vec::new();
+
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.starts_with("rootProject.name") {
+                root_name = string_literals(line).into_iter().next();
+            }
+            // project(":api").projectDir = file("services/api")
+            if line.starts_with("project(") && line.contains(".projectDir") {
+                let literals = string_literals(line);
+                if let (Some(gradle_path), Some(dir)) = (literals.first(), literals.last()) {
+                    let gradle_path = absolute_path(gradle_path);
+                    let dir = resolve_path(project_path, dir).unwrap_or_default();
+                    match projects.iter_mut().find(|(path, _)| *path == gradle_path) {
+                        Some(project) => project.1 = dir,
+                        None => projects.push((gradle_path, dir)),
+                    }
+                }
+            }
+        }
+
+        for gradle_path in included_projects(&contents) {
+            let gradle_path = absolute_path(&gradle_path);
+            if projects.iter().any(|(path, _)| *path == gradle_path) {
+                continue;
+            }
+            // the directory matches the path by default, like `:services:api` -> `services/api`
+            let relative_dir = gradle_path.trim_start_matches(':').replace(':', "/");
+            if let Some(dir) = resolve_path(project_path, &relative_dir) {
+                projects.push((gradle_path, dir));
+            }
+        }
+        debug!("Gradle projects: {:?}", projects);
+
+        GradleSettings {
+            root: project_path.to_string(),
+            root_name,
+            projects,
+        }
+    }
+
+    /// Finds the closest settings file containing the `project_path` directory, like Gradle does.
+    pub fn find(workspace_root: &Path, project_path: &str) -> Result<Option<Self>> {
+        for dir in Path::new(project_path).ancestors() {
+            let dir_path = workspace_root.join(dir);
+            if SETTINGS_FILES
+                .iter()
+                .any(|name| dir_path.join(name).is_file())
+            {
+                return Self::load(workspace_root, &dir.to_string_lossy()).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Checks whether the project located at `project_path` is the root or one of the included projects
+    pub fn contains(&self, project_path: &str) -> bool {
+        self.root == project_path || self.projects.iter().any(|(_, dir)| dir == project_path)
+    }
+
+    /// Returns the directory of the project with the Gradle path
+    pub fn project_dir(&self, gradle_path: &str) -> Option<&str> {
+        if gradle_path == ":" {
+            return Some(&self.root);
+        }
+        self.projects
+            .iter()
+            .find(|(path, _)| path == gradle_path)
+            .map(|(_, dir)| dir.as_str())
+    }
+
+    /// Returns the Gradle path of the project located at `project_path`
+    pub fn gradle_path(&self, project_path: &str) -> Option<&str> {
+        if self.root == project_path {
+            return Some(":");
+        }
+        self.projects
+            .iter()
+            .find(|(_, dir)| dir == project_path)
+            .map(|(path, _)| path.as_str())
+    }
+}
+
+/// The Gradle builds of the workspace, each settings file is parsed once and shared by its projects
+#[derive(Debug)]
+pub struct GradleBuilds {
+    workspace_root: PathBuf,
+    /// Settings by build directory
+    settings: HashMap<String, GradleSettings>,
+}
+
+impl GradleBuilds {
+    pub fn new(workspace_root: &Path) -> Self {
+        GradleBuilds {
+            workspace_root: workspace_root.to_path_buf(),
+            settings: HashMap::new(),
+        }
+    }
+
+    /// Finds the closest settings file containing the `project_path` directory, like [`GradleSettings::find`].
+    pub fn find(&mut self, project_path: &str) -> Result<Option<&GradleSettings>> {
+        let Some(dir) = Path::new(project_path).ancestors().find(|dir| {
+            let dir_path = self.workspace_root.join(dir);
+            SETTINGS_FILES
+                .iter()
+                .any(|name| dir_path.join(name).is_file())
+        }) else {
+            return Ok(None);
+        };
+
+        let dir = dir.to_string_lossy().to_string();
+        if !self.settings.contains_key(&dir) {
+            let settings = GradleSettings::load(&self.workspace_root, &dir)?;
+            self.settings.insert(dir.clone(), settings);
+        }
+        Ok(self.settings.get(&dir))
+    }
+}
+
+/// Returns the arguments of the `include 'a', 'b'` and `include("a", "b")` statements,
+/// including the ones spanning multiple lines
+fn included_projects(contents: &str) -> Vec<String> {
+    let mut result = Vec::new();
+    let mut rest = contents;
+
+    while let Some(index) = rest.find("include") {
+        let preceded_by_identifier = rest[..index]
+            .chars()
+            .next_back()
+            .is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '.');
+        rest = &rest[index + "include".len()..];
+        // skip `includeBuild`, `includeFlat` and the like
+        if preceded_by_identifier || !rest.starts_with([' ', '\t', '(']) {
+            continue;
+        }
+
+        let mut arguments = rest.trim_start().trim_start_matches('(');
+        loop {
+            arguments = arguments.trim_start();
+            let Some(quote) = arguments.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+                break;
+            };
+            let Some(end) = arguments[1..].find(quote) else {
+                break;
+            };
+            result.push(arguments[1..end + 1].to_string());
+            arguments = arguments[end + 2..].trim_start();
+            match arguments.strip_prefix(',') {
+                Some(next) => arguments = next,
+                None => break,
+            }
+        }
+    }
+
+    result
+}
+
+/// Returns the absolute Gradle path, like `:api` for `api`
+fn absolute_path(gradle_path: &str) -> String {
+    if gradle_path.starts_with(':') {
+        gradle_path.to_string()
+    } else {
+        format!(":{}", gradle_path)
+    }
+}
+
+/// Removes the `//` line comments
+pub(super) fn strip_comments(contents: &str) -> String {
+    contents
+        .lines()
+        .map(|line| line.split("//").next().unwrap_or_default())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Returns the contents of the `"..."` and `'...'` literals of the line
+pub(super) fn string_literals(line: &str) -> Vec<String> {
+    let mut result = Vec::new();
+    let mut rest = line;
+    while let Some(start) = rest.find(['"', '\'']) {
+        let quote = &rest[start..start + 1];
+        let Some(end) = rest[start + 1..].find(quote) else {
+            break;
+        };
+        result.push(rest[start + 1..start + 1 + end].to_string());
+        rest = &rest[start + end + 2..];
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_included_projects() {
+        assert_eq!(
+            included_projects("include 'app', ':libs:core'\ninclude(\"api\")\n"),
+            ["app", ":libs:core", "api"]
+        );
+        assert_eq!(
+            included_projects("include(\n    \"a\",\n    \"b\"\n)\n"),
+            ["a", "b"]
+        );
+        assert!(included_projects("includeBuild(\"build-logic\")\nincludeFlat 'x'\n").is_empty());
+        assert!(included_projects("rootProject.include 'x'\n").is_empty());
+    }
+
+    #[test]
+    fn parses_settings() {
+        let settings = GradleSettings::parse(
+            "java",
+            r#"
+// include "commented"
+rootProject.name = "shop"
+include(":services:api", "web")
+include ':legacy'
+project(":legacy").projectDir = file("old/legacy")
+"#,
+        );
+
+        assert_eq!(settings.root_name.as_deref(), Some("shop"));
+        assert_eq!(
+            settings.project_dir(":services:api"),
+            Some("java/services/api")
+        );
+        assert_eq!(settings.project_dir(":web"), Some("java/web"));
+        assert_eq!(settings.project_dir(":legacy"), Some("java/old/legacy"));
+        assert_eq!(settings.project_dir(":"), Some("java"));
+        assert_eq!(settings.project_dir(":commented"), None);
+        assert_eq!(settings.gradle_path("java/old/legacy"), Some(":legacy"));
+        assert_eq!(settings.gradle_path("java"), Some(":"));
+        assert!(settings.contains("java/services/api"));
+        assert!(!settings.contains("java/services"));
+    }
+
+    #[test]
+    fn extracts_string_literals() {
+        assert_eq!(
+            string_literals(r#"project(":a").projectDir = file('b')"#),
+            [":a", "b"]
+        );
+        assert!(string_literals("\"unterminated").is_empty());
+        assert_eq!(strip_comments("a // b\n// c\nd"), "a \n\nd");
+    }
+}
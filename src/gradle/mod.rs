@@ -0,0 +1,4 @@
+mod gradle_project;
+mod gradle_settings;
+pub use gradle_project::GradleProject;
+pub use gradle_settings::{GradleBuilds, GradleSettings};
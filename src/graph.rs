@@ -1,5 +1,6 @@
 use crate::workspace::WorkspaceGraph;
 use log::debug;
+use petgraph::visit::EdgeRef;
 use std::collections::HashSet;
 
 #[derive(Debug, Clone)]
@@ -11,8 +12,39 @@ pub enum NodeType {
 #[derive(Debug, Default, Clone)]
 pub struct ProjectNode {
     pub name: String,
+    /// Project's location relative to the root of the workspace
+    pub root: String,
     pub path: Option<String>,
     pub implicit_dependencies: Option<Vec<String>>,
+    pub tags: Vec<String>,
+    /// Dependencies declared in the project manifest, resolved against other workspace projects
+    pub dependencies: Vec<Dependency>,
+}
+
+/// A dependency declared in a project manifest (e.g. `package.json`)
+#[derive(Debug, Clone)]
+pub struct Dependency {
+    /// The name of the dependency, matched against the names of the workspace projects
+    pub name: String,
+    /// The location of the dependency relative to the root of the workspace (e.g. Cargo `path` dependencies).
+    /// Takes precedence over the name when set.
+    pub path: Option<String>,
+    pub kind: DependencyType,
+}
+
+/// The type of an edge between two projects
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DependencyType {
+    /// `implicitDependencies` of Nx projects
+    Implicit,
+    /// Runtime dependencies (e.g. `dependencies` in `package.json`)
+    Production,
+    /// Development dependencies (e.g. `devDependencies` in `package.json`)
+    Development,
+    /// Build dependencies (e.g. `[build-dependencies]` in `Cargo.toml`)
+    Build,
+    /// Peer dependencies (e.g. `peerDependencies` in `package.json`)
+    Peer,
 }
 
 #[derive(Debug, Clone)]
@@ -41,6 +73,37 @@ pub fn check_graph_recursively(
     affected_projects
 }
 
+/// Returns the projects depending on the given ones, directly or transitively
+pub fn find_dependents(graph: &WorkspaceGraph, projects: &HashSet<String>) -> HashSet<String> {
+    let mut stack: Vec<_> = graph
+        .node_indices()
+        .filter(|index| {
+            matches!(&graph[*index], NodeType::Project(project) if projects.contains(&project.name))
+        })
+        .collect();
+    let mut visited = HashSet::new();
+    let mut dependents = HashSet::new();
+
+    while let Some(node_index) = stack.pop() {
+        for neighbor in graph.neighbors_directed(node_index, petgraph::Direction::Incoming) {
+            if !visited.insert(neighbor) {
+                continue;
+            }
+            if let NodeType::Project(project_node) = &graph[neighbor] {
+                debug!("Dependent project: {}", project_node.name);
+                dependents.insert(project_node.name.clone());
+                stack.push(neighbor);
+            }
+        }
+    }
+
+    dependents
+}
+
+/// Visits the projects connected to the affected one.
+/// The `implicitDependencies` are followed in both directions, as before,
+/// while the manifest dependencies only affect the dependents:
+/// a dependency is not affected because its dependent changed.
 fn dfs_visit(
     graph: &WorkspaceGraph,
     node_index: petgraph::graph::NodeIndex,
@@ -60,13 +123,17 @@ fn dfs_visit(
         // Add the project name to affected_projects
         affected_projects.insert(project_node.name.clone());
 
-        let neighbors: Vec<_> = graph.neighbors(node_index).collect();
+        let neighbors: Vec<_> = graph
+            .edges_directed(node_index, petgraph::Direction::Outgoing)
+            .filter(|edge| *edge.weight() == DependencyType::Implicit)
+            .map(|edge| edge.target())
+            .collect();
         debug!(
             "Neighbors (Outgoing) of {}: {:?}",
             project_node.name, neighbors
         );
 
-        for neighbor in graph.neighbors(node_index) {
+        for neighbor in neighbors {
             let neighbor_node = &graph[neighbor];
             if let NodeType::Project(neighbor_project) = neighbor_node {
                 debug!(
@@ -88,12 +155,78 @@ fn dfs_visit(
         for neighbor in incoming_neighbors {
             let neighbor_node = &graph[neighbor];
             if let NodeType::Project(neighbor_project) = neighbor_node {
-                debug!(
-                    "{} <-> (implicit) <-> {}",
-                    neighbor_project.name, project_node.name
-                );
+                debug!("{} -> {}", neighbor_project.name, project_node.name);
                 dfs_visit(graph, neighbor, visited, affected_projects);
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn add_project(graph: &mut WorkspaceGraph, name: &str) -> petgraph::graph::NodeIndex {
+        graph.add_node(NodeType::Project(ProjectNode {
+            name: name.to_string(),
+            root: format!("packages/{}", name),
+            ..Default::default()
+        }))
+    }
+
+    fn names(values: &[&str]) -> HashSet<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    /// app1 and app2 both depend on utils through `workspace:*`
+    fn workspace_graph(kind: DependencyType) -> WorkspaceGraph {
+        let mut graph = WorkspaceGraph::new();
+        let app1 = add_project(&mut graph, "app1");
+        let app2 = add_project(&mut graph, "app2");
+        let utils = add_project(&mut graph, "utils");
+        graph.add_edge(app1, utils, kind);
+        graph.add_edge(app2, utils, kind);
+        graph
+    }
+
+    #[test]
+    fn dependent_change_does_not_affect_dependencies() {
+        let graph = workspace_graph(DependencyType::Production);
+
+        let affected = check_graph_recursively(&graph, &names(&["app1"]));
+        assert_eq!(affected, names(&["app1"]));
+    }
+
+    #[test]
+    fn dependency_change_affects_dependents() {
+        let graph = workspace_graph(DependencyType::Production);
+
+        let affected = check_graph_recursively(&graph, &names(&["utils"]));
+        assert_eq!(affected, names(&["app1", "app2", "utils"]));
+    }
+
+    #[test]
+    fn implicit_dependencies_are_followed_both_ways() {
+        let graph = workspace_graph(DependencyType::Implicit);
+
+        let affected = check_graph_recursively(&graph, &names(&["app1"]));
+        assert_eq!(affected, names(&["app1", "app2", "utils"]));
+    }
+
+    #[test]
+    fn dependents_are_found_transitively() {
+        let mut graph = workspace_graph(DependencyType::Production);
+        let core = add_project(&mut graph, "core");
+        let utils = graph
+            .node_indices()
+            .find(|index| matches!(&graph[*index], NodeType::Project(p) if p.name == "utils"))
+            .unwrap();
+        graph.add_edge(utils, core, DependencyType::Development);
+
+        assert_eq!(
+            find_dependents(&graph, &names(&["core"])),
+            names(&["app1", "app2", "utils"])
+        );
+        assert_eq!(find_dependents(&graph, &names(&["app1"])), HashSet::new());
+    }
+}
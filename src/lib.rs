@@ -1,16 +1,26 @@
+pub mod cargo;
+pub mod ci;
+pub mod commits;
 mod config;
+mod date;
+pub mod files;
+pub mod go;
+pub mod gradle;
 pub mod graph;
 pub mod logger;
-mod node;
+pub mod maven;
+pub mod node;
 pub mod nx;
-mod projects;
+pub mod projects;
+pub mod python;
 pub mod reports;
 pub mod tasks;
 pub mod ts;
 pub mod workspace;
 
 use clap::ValueEnum;
-pub use config::Config;
+pub use config::{Config, DiffMode};
+use git2::Repository;
 use std::path::{Path, PathBuf};
 
 #[derive(ValueEnum, Clone, Debug)]
@@ -20,15 +30,13 @@ pub enum OutputFormat {
     Text,
 }
 
+/// Finds the root of the working directory for the repository containing `starting_dir`.
+///
+/// Linked worktrees and submodules, where `.git` is a file, are supported as well.
 pub fn find_git_root(starting_dir: &Path) -> Option<PathBuf> {
-    let mut current_dir = starting_dir;
+    let repo = Repository::discover(starting_dir).ok()?;
+    let workdir = repo.workdir()?;
 
-    while current_dir != current_dir.parent()? {
-        if current_dir.join(".git").exists() {
-            return Some(current_dir.to_path_buf());
-        }
-        current_dir = current_dir.parent()?;
-    }
-
-    None
+    // strip the trailing separator returned by libgit2
+    Some(workdir.components().collect())
 }
@@ -1,12 +1,13 @@
+use affected::ci::CiEnvironment;
 use affected::logger::init_logger;
 use affected::reports;
 use affected::ts;
 use affected::workspace::Workspace;
-use affected::{find_git_root, Config, OutputFormat};
+use affected::{find_git_root, Config, DiffMode, OutputFormat};
 use anyhow::Result;
 use clap::{Parser, Subcommand};
 use dotenvy::dotenv;
-use log::{debug, error, info};
+use log::{debug, error, info, warn};
 use std::path::PathBuf;
 use std::time::Instant;
 
@@ -19,9 +20,64 @@ struct Cli {
     repo: Option<PathBuf>,
 
     /// Base of the current branch (usually main). Falls back to 'main' or 'master' if not provided.
+    /// Accepts any revision or a `<base>..<head>` / `<base>...<head>` range.
     #[arg(long)]
     base: Option<String>,
 
+    /// Head revision to compare against the base. Defaults to the working directory.
+    #[arg(long)]
+    head: Option<String>,
+
+    /// Remote used to resolve remote-tracking base branches. Defaults to 'origin'.
+    #[arg(long)]
+    remote: Option<String>,
+
+    /// Fetch the base branch from the remote before calculating affected files
+    #[arg(long)]
+    fetch: bool,
+
+    /// Ignore untracked files in the working directory
+    #[arg(long)]
+    no_untracked: bool,
+
+    /// Use the most recent tag matching the glob pattern and reachable from HEAD as the base
+    #[arg(long, conflicts_with_all = ["since", "base"])]
+    since_tag: Option<String>,
+
+    /// Use the last commit made before the date as the base
+    /// (e.g. '2024-01-31', '2024-01-31T10:30:00Z', '7 days ago', '2w')
+    #[arg(long, conflicts_with = "base")]
+    since: Option<String>,
+
+    /// Only count the changes matching the Nx named input from 'nx.json' (e.g. 'production')
+    #[arg(long, conflicts_with = "target")]
+    named_input: Option<String>,
+
+    /// Only count the changes matching the inputs of the Nx target from 'nx.json' `targetDefaults` (e.g. 'build')
+    #[arg(long)]
+    target: Option<String>,
+
+    /// The similarity threshold in percent to detect renamed and copied files. Defaults to 50.
+    #[arg(long, value_parser = clap::value_parser!(u16).range(0..=100))]
+    similarity: Option<u16>,
+
+    /// Which changes to compare against the base
+    #[arg(long)]
+    mode: Option<DiffMode>,
+
+    /// Compare against the tip of the base instead of the merge base with HEAD
+    #[arg(long)]
+    no_merge_base: bool,
+
+    /// Report the files changed inside submodules instead of the submodule paths
+    #[arg(long)]
+    submodules: bool,
+
+    /// Derive the base and head from the CI environment variables.
+    /// Enabled automatically when the `CI` environment variable is set to `true`.
+    #[arg(long)]
+    ci: bool,
+
     /// The subcommand to run
     #[command(subcommand)]
     command: Commands,
@@ -48,9 +104,23 @@ enum Commands {
         tasks: Vec<String>,
     },
 
+    /// Inspect the CI environment
+    #[command(subcommand)]
+    Ci(CiCommands),
+
     Test,
 }
 
+#[derive(Subcommand)]
+enum CiCommands {
+    /// View the CI provider, base and head detected from the environment variables
+    Info {
+        /// Output format
+        #[arg(long, default_value = "table")]
+        format: OutputFormat,
+    },
+}
+
 #[derive(Subcommand)]
 enum ViewCommands {
     /// View affected files
@@ -65,6 +135,12 @@ enum ViewCommands {
         #[arg(long, default_value = "table")]
         format: OutputFormat,
     },
+    /// View commits between the base and the head, and the affected files and projects they touched
+    Commits {
+        /// Output format
+        #[arg(long, default_value = "table")]
+        format: OutputFormat,
+    },
     /// View tasks defined in the configuration.
     Tasks {
         /// Output format
@@ -93,7 +169,7 @@ async fn main() -> Result<()> {
     let base = cli.base.clone().or(Some("main".to_string()));
 
     let config_path = workspace_root.join(".affected.yml");
-    let config = if config_path.exists() {
+    let mut config = if config_path.exists() {
         debug!("Config file found at {:?}", &config_path);
         Config::from_file(&config_path)?
     } else {
@@ -104,6 +180,62 @@ async fn main() -> Result<()> {
         }
     };
 
+    if cli.ci || config.ci.unwrap_or_else(CiEnvironment::is_ci) {
+        match CiEnvironment::detect() {
+            Some(ci) => {
+                debug!("CI provider: {}", ci.provider);
+                if ci.base.is_some() {
+                    config.base = ci.base;
+                    config.since_tag = None;
+                    config.since = None;
+                }
+                if ci.head.is_some() {
+                    config.head = ci.head;
+                }
+            }
+            None => warn!("CI mode is enabled, but no CI environment was detected"),
+        }
+    }
+
+    // an explicit base takes precedence over the `since` settings of the configuration file
+    if cli.base.is_some() {
+        config.base = cli.base.clone();
+        config.since_tag = None;
+        config.since = None;
+    }
+    if cli.head.is_some() {
+        config.head = cli.head.clone();
+    }
+    if cli.since_tag.is_some() || cli.since.is_some() {
+        config.since_tag = cli.since_tag.clone();
+        config.since = cli.since.clone();
+    }
+    if cli.named_input.is_some() || cli.target.is_some() {
+        config.named_input = cli.named_input.clone();
+        config.target = cli.target.clone();
+    }
+    if cli.remote.is_some() {
+        config.remote = cli.remote.clone();
+    }
+    if cli.fetch {
+        config.fetch = Some(true);
+    }
+    if cli.no_untracked {
+        config.untracked = Some(false);
+    }
+    if cli.similarity.is_some() {
+        config.similarity = cli.similarity;
+    }
+    if cli.mode.is_some() {
+        config.mode = cli.mode;
+    }
+    if cli.submodules {
+        config.submodules = Some(true);
+    }
+    if cli.no_merge_base {
+        config.merge_base = Some(false);
+    }
+
     let mut workspace = Workspace::with_config(&workspace_root, config);
 
     match &cli.command {
@@ -123,21 +255,28 @@ async fn main() -> Result<()> {
         Commands::View(subcommand) => match subcommand {
             ViewCommands::Files { format } => {
                 if let Err(err) = workspace.load().await {
-                    log::error!("Failed to load workspace: {}", err);
+                    log::error!("Failed to load workspace: {:#}", err);
                     return Ok(());
                 }
                 reports::display_affected_files(&workspace, format)?;
             }
             ViewCommands::Projects { format } => {
                 if let Err(err) = workspace.load().await {
-                    log::error!("Failed to load workspace: {}", err);
+                    log::error!("Failed to load workspace: {:#}", err);
                     return Ok(());
                 }
                 reports::display_affected_projects(&workspace, format)?;
             }
+            ViewCommands::Commits { format } => {
+                if let Err(err) = workspace.load().await {
+                    log::error!("Failed to load workspace: {:#}", err);
+                    return Ok(());
+                }
+                reports::display_affected_commits(&workspace, format)?;
+            }
             ViewCommands::Tasks { format } => {
                 if let Err(err) = workspace.load().await {
-                    log::error!("Failed to load workspace: {}", err);
+                    log::error!("Failed to load workspace: {:#}", err);
                     return Ok(());
                 }
                 reports::display_tasks(&workspace, format)?;
@@ -162,9 +301,14 @@ async fn main() -> Result<()> {
             let elapsed = now.elapsed();
             info!("Done ({:.2?})", elapsed);
         }
+        Commands::Ci(subcommand) => match subcommand {
+            CiCommands::Info { format } => {
+                reports::display_ci_info(CiEnvironment::detect().as_ref(), format)?;
+            }
+        },
         Commands::Test => {
             if let Err(err) = workspace.load().await {
-                log::error!("Failed to load workspace: {}", err);
+                log::error!("Failed to load workspace: {:#}", err);
                 return Ok(());
             }
 
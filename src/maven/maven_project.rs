@@ -0,0 +1,250 @@
+use crate::graph::{Dependency, DependencyType};
+use crate::maven::MavenReactor;
+use crate::projects::Project;
+use anyhow::{Context, Result};
+use log::debug;
+use roxmltree::{Document, Node};
+use std::fs;
+use std::path::Path;
+
+/// The parts of a `pom.xml` file used to build the graph
+#[derive(Debug, Default)]
+pub(super) struct Pom {
+    pub group_id: Option<String>,
+    pub artifact_id: String,
+    /// `groupId:artifactId` of the parent POM
+    pub parent: Option<String>,
+    /// Module directories relative to the POM
+    pub modules: Vec<String>,
+    /// `groupId:artifactId` and `scope` of the dependencies
+    pub dependencies: Vec<(String, Option<String>)>,
+}
+
+impl Pom {
+    /// Reads the `pom.xml` file located in the `project_path` directory
+    pub fn load(workspace_root: &Path, project_path: &str) -> Result<Self> {
+        let path = workspace_root.join(project_path).join("pom.xml");
+        debug!("Loading POM from {:?}", path);
+        let contents =
+            fs::read_to_string(&path).with_context(|| format!("Could not read {:?}", path))?;
+        Self::parse(&contents).with_context(|| format!("Could not parse {:?}", path))
+    }
+
+    pub fn parse(contents: &str) -> Result<Self> {
+        let document = Document::parse(contents)?;
+        let project = document.root_element();
+
+        let parent = child(project, "parent");
+        let parent_group_id = parent.and_then(|parent| text(parent, "groupId"));
+        // the group is inherited from the parent when omitted
+        let group_id = text(project, "groupId").or_else(|| parent_group_id.clone());
+        let artifact_id = text(project, "artifactId").context("Missing artifactId")?;
+
+        let coordinates = |node: Node| -> Option<String> {
+            let node_group_id = text(node, "groupId")?;
+            let node_group_id = match node_group_id.as_str() {
+                "${project.groupId}" | "${pom.groupId}" => group_id.clone()?,
+                "${project.parent.groupId}" | "${parent.groupId}" => parent_group_id.clone()?,
+                _ => node_group_id,
+            };
+            Some(format!("{}:{}", node_group_id, text(node, "artifactId")?))
+        };
+
+        let modules = child(project, "modules")
+            .map(|modules| {
+                children(modules, "module")
+                    .filter_map(|module| module.text())
+                    .map(|module| module.trim().trim_end_matches("pom.xml").to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let dependencies = child(project, "dependencies")
+            .map(|dependencies| {
+                children(dependencies, "dependency")
+                    .filter_map(|dependency| {
+                        Some((coordinates(dependency)?, text(dependency, "scope")))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(Pom {
+            parent: parent.and_then(coordinates),
+            group_id,
+            artifact_id,
+            modules,
+            dependencies,
+        })
+    }
+
+    /// The `groupId:artifactId` coordinates of the POM
+    pub fn coordinates(&self) -> Option<String> {
+        Some(format!("{}:{}", self.group_id.as_ref()?, self.artifact_id))
+    }
+}
+
+fn children<'a, 'input: 'a>(
+    node: Node<'a, 'input>,
+    name: &'a str,
+) -> impl Iterator<Item = Node<'a, 'input>> + 'a {
+    node.children()
+        .filter(move |child| child.is_element() && child.tag_name().name() == name)
+}
+
+fn child<'a, 'input: 'a>(node: Node<'a, 'input>, name: &'a str) -> Option<Node<'a, 'input>> {
+    children(node, name).next()
+}
+
+fn text(node: Node, name: &str) -> Option<String> {
+    Some(child(node, name)?.text()?.trim().to_string())
+}
+
+/// A struct representing a Maven module defined by a `pom.xml` file
+#[derive(Debug)]
+pub struct MavenProject {
+    /// The `artifactId` of the module
+    pub name: String,
+    /// Project's location relative to the root of the workspace
+    pub root: String,
+    /// The parent POM and the dependencies on other modules of the reactor
+    pub dependencies: Vec<Dependency>,
+}
+
+impl MavenProject {
+    /// Loads the module, resolving the dependencies against the modules of the reactor
+    pub fn load_with_reactor(
+        workspace_root: &Path,
+        project_path: &str,
+        reactor: Option<&MavenReactor>,
+    ) -> Result<Self> {
+        let pom = Pom::load(workspace_root, project_path)?;
+        let mut dependencies = Vec::new();
+
+        if let Some(reactor) = reactor {
+            let scoped_parent = pom.parent.iter().map(|parent| (parent, None));
+            let scoped_dependencies = pom
+                .dependencies
+                .iter()
+                .map(|(coordinates, scope)| (coordinates, scope.as_deref()));
+
+            for (coordinates, scope) in scoped_parent.chain(scoped_dependencies) {
+                let Some(path) = reactor.module_dir(coordinates) else {
+                    continue;
+                };
+                dependencies.push(Dependency {
+                    name: coordinates.clone(),
+                    path: Some(path.to_string()),
+                    kind: match scope {
+                        Some("test") => DependencyType::Development,
+                        _ => DependencyType::Production,
+                    },
+                });
+            }
+        }
+
+        Ok(MavenProject {
+            name: pom.artifact_id,
+            root: project_path.to_string(),
+            dependencies,
+        })
+    }
+}
+
+impl Project for MavenProject {
+    fn name(&self) -> Option<&str> {
+        Some(&self.name)
+    }
+
+    fn load(workspace_root: &Path, project_path: &str) -> Result<Self> {
+        let reactor = MavenReactor::find(workspace_root, project_path)?;
+        Self::load_with_reactor(workspace_root, project_path, reactor.as_ref())
+    }
+
+    fn dependencies(&self) -> Vec<Dependency> {
+        self.dependencies.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_pom() {
+        let pom = Pom::parse(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<project xmlns="http://maven.apache.org/POM/4.0.0">
+  <modelVersion>4.0.0</modelVersion>
+  <parent>
+    <groupId>com.acme</groupId>
+    <artifactId>parent</artifactId>
+    <version>1.0.0</version>
+  </parent>
+  <artifactId>api</artifactId>
+  <modules>
+    <module>plugins</module>
+    <module>../shared/pom.xml</module>
+  </modules>
+  <dependencies>
+    <dependency>
+      <groupId>${project.groupId}</groupId>
+      <artifactId>core</artifactId>
+    </dependency>
+    <dependency>
+      <groupId>${project.parent.groupId}</groupId>
+      <artifactId>testing</artifactId>
+      <scope>test</scope>
+    </dependency>
+    <dependency>
+      <groupId>org.slf4j</groupId>
+      <artifactId>slf4j-api</artifactId>
+    </dependency>
+    <dependency>
+      <artifactId>missing-group</artifactId>
+    </dependency>
+  </dependencies>
+  <dependencyManagement>
+    <dependencies>
+      <dependency>
+        <groupId>com.acme</groupId>
+        <artifactId>managed</artifactId>
+      </dependency>
+    </dependencies>
+  </dependencyManagement>
+</project>
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(pom.group_id.as_deref(), Some("com.acme"));
+        assert_eq!(pom.artifact_id, "api");
+        assert_eq!(pom.coordinates().as_deref(), Some("com.acme:api"));
+        assert_eq!(pom.parent.as_deref(), Some("com.acme:parent"));
+        assert_eq!(pom.modules, ["plugins", "../shared/"]);
+        assert_eq!(
+            pom.dependencies,
+            [
+                ("com.acme:core".to_string(), None),
+                ("com.acme:testing".to_string(), Some("test".to_string())),
+                ("org.slf4j:slf4j-api".to_string(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn own_group_takes_precedence_over_the_parent() {
+        let pom = Pom::parse(
+            "<project><parent><groupId>com.acme</groupId><artifactId>parent</artifactId></parent>\
+             <groupId>com.acme.tools</groupId><artifactId>cli</artifactId></project>",
+        )
+        .unwrap();
+        assert_eq!(pom.coordinates().as_deref(), Some("com.acme.tools:cli"));
+    }
+
+    #[test]
+    fn rejects_invalid_poms() {
+        assert!(Pom::parse("<project><groupId>com.acme</groupId></project>").is_err());
+        assert!(Pom::parse("<project>").is_err());
+    }
+}
@@ -0,0 +1,118 @@
+use crate::maven::maven_project::Pom;
+use crate::projects::resolve_path;
+use anyhow::Result;
+use log::{debug, warn};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Modules declared by the `<modules>` of an aggregator `pom.xml` file, including the nested ones
+#[derive(Debug)]
+pub struct MavenReactor {
+    /// Aggregator location relative to the root of the repository
+    pub root: String,
+    /// `groupId:artifactId` coordinates and directories of the modules, including the aggregator
+    modules: Vec<(Option<String>, String)>,
+}
+
+impl MavenReactor {
+    /// Loads the modules aggregated by the `pom.xml` file located in the `project_path` directory, if any.
+    pub fn load(workspace_root: &Path, project_path: &str) -> Result<Option<Self>> {
+        let pom = Pom::load(workspace_root, project_path)?;
+        if pom.modules.is_empty() {
+            return Ok(None);
+        }
+
+        let mut modules = vec![(pom.coordinates(), project_path.to_string())];
+        let mut visited = HashSet::from([project_path.to_string()]);
+        let mut pending = vec![(project_path.to_string(), pom)];
+
+        while let Some((dir, pom)) = pending.pop() {
+            for module in &pom.modules {
+                let Some(module_dir) = resolve_path(&dir, module.trim_end_matches('/')) else {
+                    continue;
+                };
+                if !visited.insert(module_dir.clone()) {
+                    continue;
+                }
+                match Pom::load(workspace_root, &module_dir) {
+                    Ok(module_pom) => {
+                        modules.push((module_pom.coordinates(), module_dir.clone()));
+                        pending.push((module_dir, module_pom));
+                    }
+                    Err(err) => warn!("Skipping Maven module '{}': {:#}", module_dir, err),
+                }
+            }
+        }
+        debug!("Maven reactor '{}' modules: {:?}", project_path, modules);
+
+        Ok(Some(MavenReactor {
+            root: project_path.to_string(),
+            modules,
+        }))
+    }
+
+    /// Finds the outermost aggregator containing the `project_path` directory.
+    pub fn find(workspace_root: &Path, project_path: &str) -> Result<Option<Self>> {
+        let mut reactor = None;
+        for dir in Path::new(project_path).ancestors() {
+            if !workspace_root.join(dir).join("pom.xml").is_file() {
+                continue;
+            }
+            if let Some(outer) = Self::load(workspace_root, &dir.to_string_lossy())? {
+                reactor = Some(outer);
+            }
+        }
+        Ok(reactor)
+    }
+
+    /// Checks whether the module located at `project_path` belongs to the reactor
+    pub fn contains(&self, project_path: &str) -> bool {
+        self.modules.iter().any(|(_, dir)| dir == project_path)
+    }
+
+    /// Returns the directory of the module with the `groupId:artifactId` coordinates
+    pub fn module_dir(&self, coordinates: &str) -> Option<&str> {
+        self.modules
+            .iter()
+            .find(|(module, _)| module.as_deref() == Some(coordinates))
+            .map(|(_, dir)| dir.as_str())
+    }
+}
+
+/// The Maven reactors of the workspace, each aggregator is parsed once and shared by its modules
+#[derive(Debug)]
+pub struct MavenReactors {
+    workspace_root: PathBuf,
+    /// Reactors by aggregator directory, `None` for the POMs without modules
+    reactors: HashMap<String, Option<MavenReactor>>,
+}
+
+impl MavenReactors {
+    pub fn new(workspace_root: &Path) -> Self {
+        MavenReactors {
+            workspace_root: workspace_root.to_path_buf(),
+            reactors: HashMap::new(),
+        }
+    }
+
+    /// Finds the outermost aggregator containing the `project_path` directory, like [`MavenReactor::find`].
+    pub fn find(&mut self, project_path: &str) -> Result<Option<&MavenReactor>> {
+        let mut outermost = None;
+        let dirs: Vec<_> = Path::new(project_path).ancestors().collect();
+        for dir in dirs.into_iter().rev() {
+            if !self.workspace_root.join(dir).join("pom.xml").is_file() {
+                continue;
+            }
+            let dir = dir.to_string_lossy().to_string();
+            if !self.reactors.contains_key(&dir) {
+                let reactor = MavenReactor::load(&self.workspace_root, &dir)?;
+                self.reactors.insert(dir.clone(), reactor);
+            }
+            if self.reactors[&dir].is_some() {
+                outermost = Some(dir);
+                break;
+            }
+        }
+        Ok(outermost.and_then(|dir| self.reactors[&dir].as_ref()))
+    }
+}
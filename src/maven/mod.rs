@@ -0,0 +1,4 @@
+mod maven_project;
+mod maven_reactor;
+pub use maven_project::MavenProject;
+pub use maven_reactor::{MavenReactor, MavenReactors};
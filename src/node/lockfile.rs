@@ -0,0 +1,512 @@
+use anyhow::{bail, Context, Result};
+use serde_json::Value as JsonValue;
+use serde_yaml::Value as YamlValue;
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+/// The lockfile names of npm, Yarn and pnpm
+pub const LOCKFILE_NAMES: [&str; 4] = [
+    "package-lock.json",
+    "npm-shrinkwrap.json",
+    "yarn.lock",
+    "pnpm-lock.yaml",
+];
+
+/// The external packages resolved by a lockfile, by name.
+/// Packages installed in several versions are merged, as the projects depend on them by name.
+#[derive(Debug, Default)]
+pub struct Lockfile {
+    /// Resolved versions of the packages
+    versions: HashMap<String, BTreeSet<String>>,
+    /// Names of the dependencies of the packages
+    dependencies: HashMap<String, BTreeSet<String>>,
+}
+
+impl Lockfile {
+    /// Checks whether the file is a lockfile, by its name
+    pub fn is_lockfile(path: &str) -> bool {
+        let file_name = path.rsplit('/').next().unwrap_or(path);
+        LOCKFILE_NAMES.contains(&file_name)
+    }
+
+    /// Parses the lockfile contents, the format is detected by the file name
+    pub fn parse(path: &str, contents: &str) -> Result<Self> {
+        let file_name = path.rsplit('/').next().unwrap_or(path);
+        let mut lockfile = Lockfile::default();
+        match file_name {
+            "package-lock.json" | "npm-shrinkwrap.json" => lockfile.parse_npm(contents)?,
+            "yarn.lock" if contents.contains("__metadata:") => {
+                lockfile.parse_yarn_berry(contents)?
+            }
+            "yarn.lock" => lockfile.parse_yarn_classic(contents),
+            "pnpm-lock.yaml" => lockfile.parse_pnpm(contents)?,
+            _ => bail!("Unsupported lockfile '{}'", path),
+        }
+        Ok(lockfile)
+    }
+
+    /// Returns the packages whose versions differ between the lockfiles,
+    /// including the added and removed ones
+    pub fn changed_packages(base: &Lockfile, head: &Lockfile) -> HashSet<String> {
+        base.versions
+            .keys()
+            .chain(head.versions.keys())
+            .filter(|name| base.versions.get(*name) != head.versions.get(*name))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the changed packages along with the packages depending on them, directly or transitively
+    pub fn dependents(&self, changed: &HashSet<String>) -> HashSet<String> {
+        let mut reverse: HashMap<&str, Vec<&str>> = HashMap::new();
+        for (name, dependencies) in &self.dependencies {
+            for dependency in dependencies {
+                reverse.entry(dependency).or_default().push(name);
+            }
+        }
+
+        let mut result = changed.clone();
+        let mut stack: Vec<&str> = changed.iter().map(String::as_str).collect();
+        while let Some(name) = stack.pop() {
+            for dependent in reverse.get(name).into_iter().flatten() {
+                if result.insert(dependent.to_string()) {
+                    stack.push(dependent);
+                }
+            }
+        }
+        result
+    }
+
+    fn add_package(&mut self, name: &str, version: &str) {
+        self.versions
+            .entry(name.to_string())
+            .or_default()
+            .insert(version.to_string());
+    }
+
+    fn add_dependencies<'a>(&mut self, name: &str, dependencies: impl Iterator<Item = &'a str>) {
+        self.dependencies
+            .entry(name.to_string())
+            .or_default()
+            .extend(dependencies.map(str::to_string));
+    }
+
+    /// `package-lock.json`, both the `packages` (v2, v3) and the `dependencies` (v1) formats
+    fn parse_npm(&mut self, contents: &str) -> Result<()> {
+        let lockfile: JsonValue = serde_json::from_str(contents)?;
+
+        if let Some(packages) = lockfile.get("packages").and_then(JsonValue::as_object) {
+            for (key, entry) in packages {
+                // the workspace packages are projects, not external packages
+                let Some(index) = key.rfind("node_modules/") else {
+                    continue;
+                };
+                let name = entry
+                    .get("name")
+                    .and_then(JsonValue::as_str)
+                    .unwrap_or(&key[index + "node_modules/".len()..]);
+                let version = entry
+                    .get("version")
+                    .or_else(|| entry.get("resolved"))
+                    .and_then(JsonValue::as_str)
+                    .unwrap_or_default();
+                self.add_package(name, version);
+                for field in ["dependencies", "optionalDependencies", "peerDependencies"] {
+                    if let Some(dependencies) = entry.get(field).and_then(JsonValue::as_object) {
+                        self.add_dependencies(name, dependencies.keys().map(String::as_str));
+                    }
+                }
+            }
+        } else if let Some(dependencies) = lockfile.get("dependencies") {
+            self.parse_npm_v1_dependencies(dependencies);
+        }
+        Ok(())
+    }
+
+    fn parse_npm_v1_dependencies(&mut self, dependencies: &JsonValue) {
+        let Some(dependencies) = dependencies.as_object() else {
+            return;
+        };
+        for (name, entry) in dependencies {
+            let version = entry
+                .get("version")
+                .and_then(JsonValue::as_str)
+                .unwrap_or_default();
+            self.add_package(name, version);
+            if let Some(requires) = entry.get("requires").and_then(JsonValue::as_object) {
+                self.add_dependencies(name, requires.keys().map(String::as_str));
+            }
+            if let Some(nested) = entry.get("dependencies") {
+                self.parse_npm_v1_dependencies(nested);
+            }
+        }
+    }
+
+    /// Yarn v1 `yarn.lock`, a custom format:
+    ///
+    /// ```text
+    /// "lodash@^4.17.0", lodash@^4.17.21:
+    ///   version "4.17.21"
+    ///   dependencies:
+    ///     foo "^1.0.0"
+    /// ```
+    fn parse_yarn_classic(&mut self, contents: &str) {
+        let mut name = None;
+        let mut in_dependencies = false;
+
+        for line in contents.lines() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let indent = line.len() - line.trim_start().len();
+            let line = line.trim();
+
+            match indent {
+                0 => {
+                    let spec = line
+                        .trim_end_matches(':')
+                        .split(", ")
+                        .next()
+                        .unwrap_or_default();
+                    name = Some(package_name(spec.trim_matches('"')).to_string());
+                    in_dependencies = false;
+                }
+                2 => {
+                    in_dependencies = line == "dependencies:" || line == "optionalDependencies:";
+                    if let (Some(version), Some(name)) = (line.strip_prefix("version "), &name) {
+                        let name = name.clone();
+                        self.add_package(&name, version.trim_matches('"'));
+                    }
+                }
+                _ if in_dependencies => {
+                    if let (Some((dependency, _)), Some(name)) = (line.split_once(' '), &name) {
+                        let name = name.clone();
+                        self.add_dependencies(&name, std::iter::once(dependency.trim_matches('"')));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Yarn v2+ `yarn.lock`, a YAML file
+    fn parse_yarn_berry(&mut self, contents: &str) -> Result<()> {
+        let lockfile: YamlValue = serde_yaml::from_str(contents)?;
+        let entries = lockfile.as_mapping().context("Invalid lockfile")?;
+
+        for (key, entry) in entries {
+            let Some(key) = key.as_str() else {
+                continue;
+            };
+            // the workspace packages are projects, not external packages
+            if key == "__metadata" || key.contains("@workspace:") {
+                continue;
+            }
+            let spec = key.split(", ").next().unwrap_or_default();
+            let name = package_name(spec);
+            let version = entry
+                .get("version")
+                .and_then(yaml_to_string)
+                .unwrap_or_default();
+            self.add_package(name, &version);
+            for field in ["dependencies", "peerDependencies"] {
+                if let Some(dependencies) = entry.get(field).and_then(YamlValue::as_mapping) {
+                    self.add_dependencies(name, dependencies.keys().filter_map(YamlValue::as_str));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// `pnpm-lock.yaml`, the keys of the `packages` and `snapshots` are like
+    /// `/lodash/4.17.21` (v5), `/lodash@4.17.21` (v6) or `lodash@4.17.21` (v9)
+    fn parse_pnpm(&mut self, contents: &str) -> Result<()> {
+        let lockfile: YamlValue = serde_yaml::from_str(contents)?;
+
+        for section in ["packages", "snapshots"] {
+            let Some(packages) = lockfile.get(section).and_then(YamlValue::as_mapping) else {
+                continue;
+            };
+            for (key, entry) in packages {
+                let Some((name, version)) = key.as_str().and_then(pnpm_package) else {
+                    continue;
+                };
+                self.add_package(name, version);
+                for field in ["dependencies", "optionalDependencies"] {
+                    if let Some(dependencies) = entry.get(field).and_then(YamlValue::as_mapping) {
+                        self.add_dependencies(
+                            name,
+                            dependencies.keys().filter_map(YamlValue::as_str),
+                        );
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the package name of a descriptor, like `@scope/pkg` for `@scope/pkg@npm:^1.0.0`
+fn package_name(spec: &str) -> &str {
+    match spec.get(1..).and_then(|rest| rest.find('@')) {
+        Some(index) => &spec[..index + 1],
+        None => spec,
+    }
+}
+
+/// Returns the name and the version of a pnpm package key, without the peer dependencies suffix
+fn pnpm_package(key: &str) -> Option<(&str, &str)> {
+    let key = key.split('(').next()?;
+    let key = key.strip_prefix('/').unwrap_or(key);
+
+    // v5: `/@scope/pkg/1.0.0_react@18.0.0`, the version follows the name after a slash
+    let segments = if key.starts_with('@') { 2 } else { 1 };
+    if let Some((index, _)) = key.match_indices('/').nth(segments - 1) {
+        let name = &key[..index];
+        if !name[1..].contains('@') {
+            let version = key[index + 1..].split('_').next()?;
+            return Some((name, version));
+        }
+    }
+
+    let name = package_name(key);
+    let version = key[name.len()..].strip_prefix('@')?;
+    Some((name, version))
+}
+
+fn yaml_to_string(value: &YamlValue) -> Option<String> {
+    match value {
+        YamlValue::String(value) => Some(value.clone()),
+        YamlValue::Number(value) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(values: &[&str]) -> HashSet<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    fn versions(lockfile: &Lockfile, name: &str) -> Vec<String> {
+        lockfile
+            .versions
+            .get(name)
+            .map(|versions| versions.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn detects_lockfiles_by_name() {
+        assert!(Lockfile::is_lockfile("package-lock.json"));
+        assert!(Lockfile::is_lockfile("apps/web/yarn.lock"));
+        assert!(Lockfile::is_lockfile("pnpm-lock.yaml"));
+        assert!(!Lockfile::is_lockfile("package.json"));
+        assert!(!Lockfile::is_lockfile("docs/yarn.lock.md"));
+    }
+
+    #[test]
+    fn parses_npm_packages() {
+        let lockfile = Lockfile::parse(
+            "package-lock.json",
+            r#"{
+                "lockfileVersion": 3,
+                "packages": {
+                    "": { "name": "root" },
+                    "packages/a": { "name": "a" },
+                    "node_modules/express": { "version": "4.18.0", "dependencies": { "qs": "^6.0.0" } },
+                    "node_modules/qs": { "version": "6.10.0" },
+                    "node_modules/express/node_modules/qs": { "version": "6.9.0" },
+                    "node_modules/@scope/pkg": { "version": "1.0.0" }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(versions(&lockfile, "qs"), ["6.10.0", "6.9.0"]);
+        assert_eq!(versions(&lockfile, "@scope/pkg"), ["1.0.0"]);
+        assert!(!lockfile.versions.contains_key("a"));
+        assert!(!lockfile.versions.contains_key("root"));
+        assert_eq!(lockfile.dependencies["express"], ["qs".to_string()].into());
+    }
+
+    #[test]
+    fn parses_npm_v1_dependencies() {
+        let lockfile = Lockfile::parse(
+            "npm-shrinkwrap.json",
+            r#"{
+                "lockfileVersion": 1,
+                "dependencies": {
+                    "express": {
+                        "version": "4.18.0",
+                        "requires": { "qs": "6.10.0" },
+                        "dependencies": { "qs": { "version": "6.9.0" } }
+                    },
+                    "qs": { "version": "6.10.0" }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(versions(&lockfile, "express"), ["4.18.0"]);
+        assert_eq!(versions(&lockfile, "qs"), ["6.10.0", "6.9.0"]);
+        assert_eq!(lockfile.dependencies["express"], ["qs".to_string()].into());
+    }
+
+    #[test]
+    fn parses_yarn_classic() {
+        let lockfile = Lockfile::parse(
+            "yarn.lock",
+            r#"# THIS IS AN AUTOGENERATED FILE. DO NOT EDIT THIS FILE DIRECTLY.
+# yarn lockfile v1
+
+
+"@scope/pkg@^1.0.0":
+  version "1.2.0"
+  resolved "https://registry.yarnpkg.com/@scope/pkg/-/pkg-1.2.0.tgz"
+
+express@^4.0.0:
+  version "4.18.0"
+  dependencies:
+    qs "6.10.0"
+    "@scope/pkg" "^1.0.0"
+
+lodash@^4.0.0, lodash@^4.17.0:
+  version "4.17.21"
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(versions(&lockfile, "@scope/pkg"), ["1.2.0"]);
+        assert_eq!(versions(&lockfile, "lodash"), ["4.17.21"]);
+        assert_eq!(
+            lockfile.dependencies["express"],
+            ["qs".to_string(), "@scope/pkg".to_string()].into()
+        );
+        assert!(!lockfile.dependencies.contains_key("lodash"));
+    }
+
+    #[test]
+    fn parses_yarn_berry() {
+        let lockfile = Lockfile::parse(
+            "yarn.lock",
+            r#"__metadata:
+  version: 8
+  cacheKey: 10
+
+"@scope/pkg@npm:^1.0.0":
+  version: 1.2.0
+
+"express@npm:^4.0.0, express@npm:^4.18.0":
+  version: 4.18.0
+  dependencies:
+    qs: "npm:6.10.0"
+
+"a@workspace:packages/a":
+  version: 0.0.0-use.local
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(versions(&lockfile, "@scope/pkg"), ["1.2.0"]);
+        assert_eq!(versions(&lockfile, "express"), ["4.18.0"]);
+        assert!(!lockfile.versions.contains_key("a"));
+        assert_eq!(lockfile.dependencies["express"], ["qs".to_string()].into());
+    }
+
+    #[test]
+    fn parses_pnpm_v9() {
+        let lockfile = Lockfile::parse(
+            "pnpm-lock.yaml",
+            r#"lockfileVersion: '9.0'
+packages:
+  '@scope/pkg@1.2.0':
+    resolution: {integrity: sha512-x}
+  express@4.18.0:
+    resolution: {integrity: sha512-y}
+snapshots:
+  '@scope/pkg@1.2.0(react@18.0.0)': {}
+  express@4.18.0:
+    dependencies:
+      qs: 6.10.0
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(versions(&lockfile, "@scope/pkg"), ["1.2.0"]);
+        assert_eq!(versions(&lockfile, "express"), ["4.18.0"]);
+        assert_eq!(lockfile.dependencies["express"], ["qs".to_string()].into());
+    }
+
+    #[test]
+    fn parses_pnpm_package_keys() {
+        // v5
+        assert_eq!(pnpm_package("/lodash/4.17.21"), Some(("lodash", "4.17.21")));
+        assert_eq!(
+            pnpm_package("/@scope/pkg/1.0.0_react@18.0.0"),
+            Some(("@scope/pkg", "1.0.0"))
+        );
+        // v6
+        assert_eq!(pnpm_package("/lodash@4.17.21"), Some(("lodash", "4.17.21")));
+        assert_eq!(
+            pnpm_package("/@scope/pkg@1.0.0(react@18.0.0)"),
+            Some(("@scope/pkg", "1.0.0"))
+        );
+        // v9
+        assert_eq!(pnpm_package("lodash@4.17.21"), Some(("lodash", "4.17.21")));
+        assert_eq!(
+            pnpm_package("pkg@https://codeload.github.com/owner/pkg/tar.gz/abc"),
+            Some(("pkg", "https://codeload.github.com/owner/pkg/tar.gz/abc"))
+        );
+        assert_eq!(
+            pnpm_package("/lodash/4.17.21_react@18.0.0"),
+            Some(("lodash", "4.17.21"))
+        );
+        assert_eq!(pnpm_package("lodash"), None);
+    }
+
+    #[test]
+    fn extracts_package_names() {
+        assert_eq!(package_name("lodash@^4.0.0"), "lodash");
+        assert_eq!(package_name("@scope/pkg@npm:^1.0.0"), "@scope/pkg");
+        assert_eq!(package_name("@scope/pkg"), "@scope/pkg");
+        assert_eq!(package_name("lodash"), "lodash");
+    }
+
+    #[test]
+    fn rejects_invalid_lockfiles() {
+        assert!(Lockfile::parse("package-lock.json", "{").is_err());
+        assert!(Lockfile::parse("pnpm-lock.yaml", "packages: [").is_err());
+        assert!(Lockfile::parse("package.json", "{}").is_err());
+    }
+
+    #[test]
+    fn finds_changed_packages_and_dependents() {
+        let base = Lockfile::parse(
+            "package-lock.json",
+            r#"{ "packages": {
+                "node_modules/express": { "version": "4.18.0", "dependencies": { "qs": "^6.0.0" } },
+                "node_modules/qs": { "version": "6.10.0" },
+                "node_modules/lodash": { "version": "4.17.20" },
+                "node_modules/left-pad": { "version": "1.0.0" }
+            } }"#,
+        )
+        .unwrap();
+        let head = Lockfile::parse(
+            "package-lock.json",
+            r#"{ "packages": {
+                "node_modules/express": { "version": "4.18.0", "dependencies": { "qs": "^6.0.0" } },
+                "node_modules/qs": { "version": "6.11.0" },
+                "node_modules/lodash": { "version": "4.17.20" },
+                "node_modules/is-odd": { "version": "1.0.0" }
+            } }"#,
+        )
+        .unwrap();
+
+        let changed = Lockfile::changed_packages(&base, &head);
+        assert_eq!(changed, names(&["qs", "left-pad", "is-odd"]));
+        assert_eq!(head.dependents(&names(&["qs"])), names(&["qs", "express"]));
+        assert_eq!(head.dependents(&names(&["lodash"])), names(&["lodash"]));
+    }
+}
@@ -1 +1,6 @@
+mod lockfile;
 mod node_project;
+mod node_workspace;
+pub use lockfile::Lockfile;
+pub use node_project::NodeProject;
+pub use node_workspace::NodeWorkspace;
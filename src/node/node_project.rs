@@ -1,12 +1,48 @@
+use crate::graph::{Dependency, DependencyType};
 use crate::projects::Project;
-use anyhow::Result;
+use anyhow::{Context, Result};
+use log::debug;
 use serde::Deserialize;
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::Path;
 
+/// A struct representing a Node project defined by a `package.json` file
 #[derive(Debug, Deserialize)]
 pub struct NodeProject {
+    /// The name of the package
     pub name: Option<String>,
+    /// The version of the package
+    pub version: Option<String>,
+    /// Glob patterns of the workspace packages (npm, Yarn)
+    pub workspaces: Option<Workspaces>,
+    pub dependencies: Option<BTreeMap<String, String>>,
+    #[serde(rename = "devDependencies")]
+    pub dev_dependencies: Option<BTreeMap<String, String>>,
+    #[serde(rename = "peerDependencies")]
+    pub peer_dependencies: Option<BTreeMap<String, String>>,
+    /// Project's location relative to the root of the workspace
+    #[serde(skip)]
+    pub root: String,
+}
+
+/// The `workspaces` field, either a list of globs or a Yarn object with `packages`
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum Workspaces {
+    Patterns(Vec<String>),
+    Config { packages: Option<Vec<String>> },
+}
+
+impl NodeProject {
+    /// Returns the glob patterns of the workspace packages, if any
+    pub fn workspace_patterns(&self) -> Option<Vec<String>> {
+        match &self.workspaces {
+            Some(Workspaces::Patterns(patterns)) => Some(patterns.clone()),
+            Some(Workspaces::Config { packages }) => packages.clone(),
+            None => None,
+        }
+    }
 }
 
 impl Project for NodeProject {
@@ -16,8 +52,47 @@ impl Project for NodeProject {
 
     fn load(workspace_root: &Path, project_path: &str) -> Result<Self> {
         let path = workspace_root.join(project_path).join("package.json");
-        let contents = fs::read_to_string(path)?;
-        let project: NodeProject = serde_json::from_str(&contents)?;
+        debug!("Loading project from {:?}", path);
+        let contents =
+            fs::read_to_string(&path).with_context(|| format!("Could not read {:?}", path))?;
+        let mut project: NodeProject = serde_json::from_str(&contents)
+            .with_context(|| format!("Could not parse {:?}", path))?;
+        project.root = project_path.to_string();
         Ok(project)
     }
+
+    /// Returns all the declared dependencies, including the `workspace:` protocol ones.
+    /// Packages that are not part of the workspace are filtered out when building the graph.
+    fn dependencies(&self) -> Vec<Dependency> {
+        [
+            (&self.dependencies, DependencyType::Production),
+            (&self.dev_dependencies, DependencyType::Development),
+            (&self.peer_dependencies, DependencyType::Peer),
+        ]
+        .into_iter()
+        .filter_map(|(dependencies, kind)| dependencies.as_ref().map(|deps| (deps, kind)))
+        .flat_map(|(dependencies, kind)| {
+            dependencies.iter().map(move |(name, version)| Dependency {
+                name: aliased_package(version).unwrap_or(name).to_string(),
+                path: None,
+                kind,
+            })
+        })
+        .collect()
+    }
+}
+
+/// Returns the target package of aliased dependencies,
+/// like `"alias": "workspace:@acme/utils@*"` or `"alias": "npm:@acme/utils@^1.0.0"`
+fn aliased_package(version: &str) -> Option<&str> {
+    let spec = version
+        .strip_prefix("workspace:")
+        .or_else(|| version.strip_prefix("npm:"))?;
+
+    // skip the leading `@` of scoped packages when looking for the version separator
+    match spec.get(1..)?.find('@') {
+        Some(index) => Some(&spec[..index + 1]),
+        None if spec.starts_with('@') && spec.contains('/') => Some(spec),
+        None => None,
+    }
 }
@@ -0,0 +1,69 @@
+use crate::node::NodeProject;
+use crate::projects::Project;
+use anyhow::{Context, Result};
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use log::debug;
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+
+/// The `pnpm-workspace.yaml` file
+#[derive(Debug, Deserialize)]
+struct PnpmWorkspace {
+    packages: Option<Vec<String>>,
+}
+
+/// Packages declared by the `workspaces` field of the root `package.json` (npm, Yarn)
+/// or by the `pnpm-workspace.yaml` file (pnpm)
+#[derive(Debug)]
+pub struct NodeWorkspace {
+    include: GlobSet,
+    exclude: GlobSet,
+}
+
+impl NodeWorkspace {
+    /// Loads the workspace packages declaration, if any.
+    pub fn load(workspace_root: &Path) -> Result<Option<Self>> {
+        let pnpm_path = workspace_root.join("pnpm-workspace.yaml");
+        let patterns = if pnpm_path.is_file() {
+            debug!("Loading pnpm workspace from {:?}", pnpm_path);
+            let contents = fs::read_to_string(&pnpm_path)
+                .with_context(|| format!("Could not read {:?}", pnpm_path))?;
+            let workspace: PnpmWorkspace = serde_yaml::from_str(&contents)
+                .with_context(|| format!("Could not parse {:?}", pnpm_path))?;
+            workspace.packages
+        } else if workspace_root.join("package.json").is_file() {
+            NodeProject::load(workspace_root, "")?.workspace_patterns()
+        } else {
+            None
+        };
+
+        let Some(patterns) = patterns else {
+            return Ok(None);
+        };
+        debug!("Workspace packages: {:?}", patterns);
+
+        let mut include = GlobSetBuilder::new();
+        let mut exclude = GlobSetBuilder::new();
+
+        for pattern in &patterns {
+            let (builder, pattern) = match pattern.strip_prefix('!') {
+                Some(pattern) => (&mut exclude, pattern),
+                None => (&mut include, pattern.as_str()),
+            };
+            let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+            // `*` should match a single directory, like in npm, Yarn and pnpm
+            builder.add(GlobBuilder::new(pattern).literal_separator(true).build()?);
+        }
+
+        Ok(Some(NodeWorkspace {
+            include: include.build()?,
+            exclude: exclude.build()?,
+        }))
+    }
+
+    /// Checks whether the project directory is one of the workspace packages
+    pub fn contains(&self, project_path: &str) -> bool {
+        self.include.is_match(project_path) && !self.exclude.is_match(project_path)
+    }
+}
@@ -1,2 +1,9 @@
+mod nx_config;
 mod nx_project;
+mod nx_workspace;
+pub use nx_config::{
+    ImplicitDependency, ImplicitDependencyMatcher, Input, InputMatcher, NxConfig, ProjectInputs,
+    TargetConfiguration, TargetDefaults,
+};
 pub use nx_project::NxProject;
+pub use nx_workspace::NxWorkspace;
@@ -0,0 +1,501 @@
+use anyhow::{Context, Result};
+use globset::{GlobBuilder, GlobMatcher, GlobSet, GlobSetBuilder};
+use log::{debug, warn};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+/// The `nx.json` file
+#[derive(Debug, Default, Deserialize)]
+pub struct NxConfig {
+    /// Files affecting all (`"*"`) or the listed projects when changed
+    #[serde(default, rename = "implicitDependencies")]
+    pub implicit_dependencies: BTreeMap<String, ImplicitDependency>,
+    /// Reusable sets of inputs, like `production`
+    #[serde(default, rename = "namedInputs")]
+    pub named_inputs: BTreeMap<String, Vec<Input>>,
+    /// Default target configurations, like `build`
+    #[serde(default, rename = "targetDefaults")]
+    pub target_defaults: BTreeMap<String, TargetDefaults>,
+}
+
+/// The projects affected by a global implicit dependency
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum ImplicitDependency {
+    /// `"*"` for all the projects
+    All(String),
+    /// Names or glob patterns of the projects
+    Projects(Vec<String>),
+    /// Legacy per-field configuration, like `"package.json": { "dependencies": "*" }`
+    Fields(BTreeMap<String, ImplicitDependency>),
+}
+
+/// An input, either a file pattern (`{projectRoot}/**/*`), a named input reference (`production`, `^production`),
+/// or an object (`{ "externalDependencies": [...] }`) that does not relate to files
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum Input {
+    Value(String),
+    Object(serde_json::Value),
+}
+
+/// The default configuration of a target from `nx.json`
+pub type TargetDefaults = TargetConfiguration;
+
+/// A target configuration from the `targets` of a `project.json` file
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TargetConfiguration {
+    pub inputs: Option<Vec<Input>>,
+    #[serde(rename = "dependsOn")]
+    pub depends_on: Option<Vec<serde_json::Value>>,
+}
+
+impl NxConfig {
+    /// Loads the `nx.json` file, if any.
+    pub fn load(workspace_root: &Path) -> Result<Option<Self>> {
+        let path = workspace_root.join("nx.json");
+        if !path.is_file() {
+            return Ok(None);
+        }
+        debug!("Loading Nx configuration from {:?}", path);
+        let contents =
+            fs::read_to_string(&path).with_context(|| format!("Could not read {:?}", path))?;
+        // the invalid entries are skipped instead of failing the whole configuration
+        let value: serde_json::Value = match serde_json::from_str(&contents) {
+            Ok(value) => value,
+            Err(err) => {
+                warn!("Skipping {:?}: {}", path, err);
+                return Ok(None);
+            }
+        };
+        Ok(Some(NxConfig {
+            implicit_dependencies: parse_entries(&value, "implicitDependencies", &path),
+            named_inputs: parse_entries(&value, "namedInputs", &path),
+            target_defaults: parse_entries(&value, "targetDefaults", &path),
+        }))
+    }
+
+    /// Compiles the file patterns of the implicit dependencies, to match many files against them
+    pub fn implicit_dependency_matchers(&self) -> Result<Vec<ImplicitDependencyMatcher>> {
+        self.implicit_dependencies
+            .iter()
+            .map(|(pattern, dependency)| {
+                let pattern = pattern.trim_start_matches("./");
+                let mut projects = Vec::new();
+                dependency.collect_projects(&mut projects);
+                Ok(ImplicitDependencyMatcher {
+                    pattern: pattern.to_string(),
+                    glob: GlobBuilder::new(pattern)
+                        .literal_separator(true)
+                        .build()?
+                        .compile_matcher(),
+                    projects,
+                })
+            })
+            .collect()
+    }
+
+    /// Checks whether the named input is defined, `default` always is
+    pub fn has_named_input(&self, name: &str) -> bool {
+        name == "default" || self.named_inputs.contains_key(name)
+    }
+
+    /// Returns the inputs of the target from `targetDefaults`, if configured
+    pub fn target_inputs(&self, target: &str) -> Option<&Vec<Input>> {
+        self.target_defaults.get(target)?.inputs.as_ref()
+    }
+
+    /// Returns the file patterns of the inputs, skipping the `^` inputs of the dependencies.
+    /// The named inputs of the project take precedence over the `nx.json` ones.
+    pub fn input_patterns(
+        &self,
+        inputs: &[Input],
+        project_named_inputs: Option<&BTreeMap<String, Vec<Input>>>,
+    ) -> Vec<String> {
+        let mut patterns = Vec::new();
+        self.resolve_inputs(
+            inputs,
+            project_named_inputs,
+            &mut patterns,
+            &mut HashSet::new(),
+        );
+        patterns
+    }
+
+    /// Returns the file patterns of the `^` inputs, matched against the files of a dependency.
+    /// Like in Nx, `^production` is resolved with the named inputs of the dependency,
+    /// falling back to the `nx.json` ones.
+    pub fn dependency_patterns(
+        &self,
+        inputs: &[Input],
+        dependency_named_inputs: Option<&BTreeMap<String, Vec<Input>>>,
+    ) -> Vec<String> {
+        let inputs: Vec<_> = inputs
+            .iter()
+            .filter_map(|input| match input {
+                Input::Value(value) => value.strip_prefix('^'),
+                Input::Object(_) => None,
+            })
+            .map(|value| Input::Value(value.to_string()))
+            .collect();
+        self.input_patterns(&inputs, dependency_named_inputs)
+    }
+
+    /// Resolves the inputs of a project to the matcher of its own files,
+    /// keeping the `^` inputs to resolve them for each dependency
+    pub fn project_inputs(
+        &self,
+        inputs: &[Input],
+        project_named_inputs: Option<&BTreeMap<String, Vec<Input>>>,
+    ) -> Result<ProjectInputs> {
+        let own = self.input_patterns(inputs, project_named_inputs);
+        let dependencies: Vec<_> = inputs
+            .iter()
+            .filter(|input| matches!(input, Input::Value(value) if value.starts_with('^')))
+            .cloned()
+            .collect();
+        debug!(
+            "Project inputs: {:?}, dependency inputs: {:?}",
+            own, dependencies
+        );
+
+        Ok(ProjectInputs {
+            own: InputMatcher::new(&own)?,
+            dependencies,
+        })
+    }
+
+    fn resolve_inputs(
+        &self,
+        inputs: &[Input],
+        project_named_inputs: Option<&BTreeMap<String, Vec<Input>>>,
+        patterns: &mut Vec<String>,
+        visited: &mut HashSet<String>,
+    ) {
+        for input in inputs {
+            let Input::Value(value) = input else {
+                continue;
+            };
+            if value.starts_with('{') || value.starts_with('!') {
+                patterns.push(value.clone());
+            } else if value.starts_with('^') {
+                // the nested dependency inputs do not change which files belong to the project
+                debug!("Skipping dependency input '{}'", value);
+            } else if visited.insert(value.clone()) {
+                let named_inputs = project_named_inputs
+                    .and_then(|named_inputs| named_inputs.get(value))
+                    .or_else(|| self.named_inputs.get(value));
+                match named_inputs {
+                    Some(named_inputs) => {
+                        self.resolve_inputs(named_inputs, project_named_inputs, patterns, visited)
+                    }
+                    // all the project files, like in Nx
+                    None if value == "default" => patterns.push("{projectRoot}/**/*".to_string()),
+                    None => debug!("Unknown named input '{}'", value),
+                }
+            }
+        }
+    }
+}
+
+/// Deserializes the entries of the object field one by one, skipping the invalid ones with a warning
+pub(super) fn parse_entries<T: DeserializeOwned>(
+    value: &serde_json::Value,
+    field: &str,
+    path: &Path,
+) -> BTreeMap<String, T> {
+    let Some(entries) = value.get(field) else {
+        return BTreeMap::new();
+    };
+    let Some(entries) = entries.as_object() else {
+        warn!("Skipping '{}' in {:?}: expected an object", field, path);
+        return BTreeMap::new();
+    };
+    entries
+        .iter()
+        .filter_map(|(key, entry)| match T::deserialize(entry) {
+            Ok(entry) => Some((key.clone(), entry)),
+            Err(err) => {
+                warn!("Skipping '{}.{}' in {:?}: {}", field, key, path, err);
+                None
+            }
+        })
+        .collect()
+}
+
+/// The inputs of a project: the files of the project itself,
+/// and the `^` inputs it relies on, resolved with the named inputs of each dependency
+#[derive(Debug)]
+pub struct ProjectInputs {
+    pub own: InputMatcher,
+    pub dependencies: Vec<Input>,
+}
+
+/// A compiled implicit dependency from `nx.json`
+#[derive(Debug)]
+pub struct ImplicitDependencyMatcher {
+    pattern: String,
+    glob: GlobMatcher,
+    /// Names or glob patterns of the affected projects, `"*"` stands for all the projects
+    pub projects: Vec<String>,
+}
+
+impl ImplicitDependencyMatcher {
+    /// Checks whether the changed file is the implicit dependency
+    pub fn is_match(&self, path: &str) -> bool {
+        self.pattern == path || self.glob.is_match(path)
+    }
+}
+
+impl ImplicitDependency {
+    fn collect_projects(&self, result: &mut Vec<String>) {
+        match self {
+            ImplicitDependency::All(value) => result.push(value.clone()),
+            ImplicitDependency::Projects(projects) => result.extend(projects.iter().cloned()),
+            // any change to the file counts, the individual fields are not compared
+            ImplicitDependency::Fields(fields) => {
+                for dependency in fields.values() {
+                    dependency.collect_projects(result);
+                }
+            }
+        }
+    }
+}
+
+/// Matches the changed files against the `{projectRoot}` and `{workspaceRoot}` input patterns
+#[derive(Debug)]
+pub struct InputMatcher {
+    project_include: GlobSet,
+    project_exclude: GlobSet,
+    workspace_include: GlobSet,
+    workspace_exclude: GlobSet,
+}
+
+impl InputMatcher {
+    pub fn new(patterns: &[String]) -> Result<Self> {
+        let mut project_include = GlobSetBuilder::new();
+        let mut project_exclude = GlobSetBuilder::new();
+        let mut workspace_include = GlobSetBuilder::new();
+        let mut workspace_exclude = GlobSetBuilder::new();
+
+        for pattern in patterns {
+            let (pattern, excluded) = match pattern.strip_prefix('!') {
+                Some(pattern) => (pattern, true),
+                None => (pattern.as_str(), false),
+            };
+            let (builder, pattern) = if let Some(pattern) = pattern.strip_prefix("{projectRoot}") {
+                let builder = if excluded {
+                    &mut project_exclude
+                } else {
+                    &mut project_include
+                };
+                (builder, pattern)
+            } else {
+                let pattern = pattern.strip_prefix("{workspaceRoot}").unwrap_or(pattern);
+                let builder = if excluded {
+                    &mut workspace_exclude
+                } else {
+                    &mut workspace_include
+                };
+                (builder, pattern)
+            };
+            let pattern = pattern.trim_start_matches('/');
+            let pattern = if pattern.is_empty() { "**" } else { pattern };
+            builder.add(GlobBuilder::new(pattern).literal_separator(true).build()?);
+        }
+        debug!("Input patterns: {:?}", patterns);
+
+        Ok(InputMatcher {
+            project_include: project_include.build()?,
+            project_exclude: project_exclude.build()?,
+            workspace_include: workspace_include.build()?,
+            workspace_exclude: workspace_exclude.build()?,
+        })
+    }
+
+    /// Checks whether the changed file is an input of the project.
+    /// The `{projectRoot}` patterns only apply to the files `owned` by the project.
+    pub fn matches(&self, path: &str, project_root: &str, owned: bool) -> bool {
+        let relative_path = owned
+            .then(|| Path::new(path).strip_prefix(project_root).ok())
+            .flatten();
+        let included = self.workspace_include.is_match(path)
+            || relative_path.is_some_and(|relative| self.project_include.is_match(relative));
+        let excluded = self.workspace_exclude.is_match(path)
+            || relative_path.is_some_and(|relative| self.project_exclude.is_match(relative));
+        included && !excluded
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NX_JSON: &str = r#"{
+        "namedInputs": {
+            "default": ["{projectRoot}/**/*", "sharedGlobals"],
+            "production": ["default", "!{projectRoot}/**/*.spec.ts"],
+            "sharedGlobals": ["{workspaceRoot}/babel.config.json"]
+        },
+        "targetDefaults": {
+            "build": { "inputs": ["production", "^production"] }
+        }
+    }"#;
+
+    fn inputs(values: &[&str]) -> Vec<Input> {
+        values
+            .iter()
+            .map(|value| Input::Value(value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn project_inputs_resolve_named_inputs() {
+        let config: NxConfig = serde_json::from_str(NX_JSON).unwrap();
+        let production = config
+            .project_inputs(&inputs(&["production"]), None)
+            .unwrap();
+        assert!(production
+            .own
+            .matches("libs/ui/src/button.ts", "libs/ui", true));
+        assert!(!production
+            .own
+            .matches("libs/ui/src/button.spec.ts", "libs/ui", true));
+        assert!(!production
+            .own
+            .matches("libs/other/src/button.ts", "libs/ui", false));
+        assert!(production
+            .own
+            .matches("babel.config.json", "libs/ui", false));
+        assert!(production.dependencies.is_empty());
+
+        let missing = config.project_inputs(&inputs(&["missing"]), None).unwrap();
+        assert!(!missing
+            .own
+            .matches("libs/ui/src/button.ts", "libs/ui", true));
+
+        let default = NxConfig::default()
+            .project_inputs(&inputs(&["default"]), None)
+            .unwrap();
+        assert!(default
+            .own
+            .matches("libs/ui/src/button.spec.ts", "libs/ui", true));
+        assert!(!default.own.matches("babel.config.json", "libs/ui", false));
+    }
+
+    #[test]
+    fn project_inputs_prefer_the_project_named_inputs() {
+        let config: NxConfig = serde_json::from_str(NX_JSON).unwrap();
+        let project_named_inputs = BTreeMap::from([(
+            "production".to_string(),
+            inputs(&["{projectRoot}/src/**/*"]),
+        )]);
+        let production = config
+            .project_inputs(&inputs(&["production"]), Some(&project_named_inputs))
+            .unwrap();
+        assert!(production
+            .own
+            .matches("libs/ui/src/button.spec.ts", "libs/ui", true));
+        assert!(!production.own.matches("libs/ui/README.md", "libs/ui", true));
+        assert!(!production
+            .own
+            .matches("babel.config.json", "libs/ui", false));
+    }
+
+    #[test]
+    fn target_inputs() {
+        let config: NxConfig = serde_json::from_str(NX_JSON).unwrap();
+        let build = config
+            .project_inputs(config.target_inputs("build").unwrap(), None)
+            .unwrap();
+        assert!(build.own.matches("libs/ui/src/button.ts", "libs/ui", true));
+        assert!(!build
+            .own
+            .matches("libs/ui/src/button.spec.ts", "libs/ui", true));
+        assert_eq!(build.dependencies.len(), 1);
+        assert!(config.target_inputs("test").is_none());
+    }
+
+    #[test]
+    fn dependency_patterns_use_the_named_inputs_of_the_dependency() {
+        let config: NxConfig = serde_json::from_str(NX_JSON).unwrap();
+        // the library excludes its own jest configuration from `production`
+        let library_named_inputs = BTreeMap::from([(
+            "production".to_string(),
+            inputs(&["default", "!{projectRoot}/jest.config.ts"]),
+        )]);
+        let dependent = config
+            .project_inputs(&inputs(&["production", "^production"]), None)
+            .unwrap();
+
+        let matcher = |named_inputs| {
+            let patterns = config.dependency_patterns(&dependent.dependencies, named_inputs);
+            InputMatcher::new(&patterns).unwrap()
+        };
+        let library = matcher(Some(&library_named_inputs));
+        assert!(library.matches("libs/ui/src/button.ts", "libs/ui", true));
+        assert!(library.matches("libs/ui/src/button.spec.ts", "libs/ui", true));
+        assert!(!library.matches("libs/ui/jest.config.ts", "libs/ui", true));
+
+        // without the override, the `nx.json` definition applies
+        let library = matcher(None);
+        assert!(library.matches("libs/ui/jest.config.ts", "libs/ui", true));
+        assert!(!library.matches("libs/ui/src/button.spec.ts", "libs/ui", true));
+    }
+
+    #[test]
+    fn implicit_dependency_matchers() {
+        let config: NxConfig = serde_json::from_str(
+            r#"{
+                "implicitDependencies": {
+                    "./package.json": { "dependencies": "*", "devDependencies": ["shop-*"] },
+                    "*.json": ["api"],
+                    "ci/**/*.sh": "*"
+                }
+            }"#,
+        )
+        .unwrap();
+        let matchers = config.implicit_dependency_matchers().unwrap();
+        let affected = |path: &str| {
+            matchers
+                .iter()
+                .filter(|matcher| matcher.is_match(path))
+                .flat_map(|matcher| matcher.projects.clone())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(affected("package.json"), ["api", "*", "shop-*"]);
+        assert_eq!(affected("tsconfig.json"), ["api"]);
+        assert!(affected("libs/ui/package.json").is_empty());
+        assert_eq!(affected("ci/jobs/test.sh"), ["*"]);
+    }
+
+    #[test]
+    fn skips_invalid_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("nx.json"),
+            r#"{
+                "implicitDependencies": { "package.json": "*", "tsconfig.json": 42 },
+                "namedInputs": { "default": ["{projectRoot}/**/*"], "production": "default" },
+                "targetDefaults": { "build": { "inputs": ["production"] }, "test": { "inputs": "default" } },
+                "plugins": ["@nx/js"]
+            }"#,
+        )
+        .unwrap();
+
+        let config = NxConfig::load(dir.path()).unwrap().unwrap();
+        assert_eq!(
+            config.implicit_dependencies.keys().collect::<Vec<_>>(),
+            ["package.json"]
+        );
+        assert_eq!(config.named_inputs.keys().collect::<Vec<_>>(), ["default"]);
+        assert_eq!(config.target_defaults.keys().collect::<Vec<_>>(), ["build"]);
+
+        fs::write(dir.path().join("nx.json"), "{ invalid").unwrap();
+        assert!(NxConfig::load(dir.path()).unwrap().is_none());
+    }
+}
@@ -1,7 +1,9 @@
+use crate::nx::{Input, TargetConfiguration};
 use crate::projects::Project;
-use anyhow::Result;
+use anyhow::{Context, Result};
 use log::debug;
 use serde::Deserialize;
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::Path;
 
@@ -21,6 +23,10 @@ pub struct NxProject {
     pub tags: Option<Vec<String>>,
     #[serde(rename = "implicitDependencies")]
     pub implicit_dependencies: Option<Vec<String>>,
+    /// Named inputs overriding the ones from `nx.json`
+    #[serde(rename = "namedInputs")]
+    pub named_inputs: Option<BTreeMap<String, Vec<Input>>>,
+    pub targets: Option<BTreeMap<String, TargetConfiguration>>,
 }
 
 /// An enum representing the type of project
@@ -39,10 +45,10 @@ impl Project for NxProject {
     fn load(workspace_root: &Path, project_path: &str) -> Result<Self> {
         let path = workspace_root.join(project_path).join("project.json");
         debug!("Loading project from {:?}", path);
-        let contents = fs::read_to_string(path)
-            .unwrap_or_else(|_| panic!("Could not read {}/project.json", &project_path));
+        let contents = fs::read_to_string(&path)
+            .with_context(|| format!("Could not read {}/project.json", &project_path))?;
         let mut project: NxProject = serde_json::from_str(&contents)
-            .unwrap_or_else(|_| panic!("Could not parse {}/project.json", &project_path));
+            .with_context(|| format!("Could not parse {}/project.json", &project_path))?;
 
         if project.root.is_none() {
             project.root = Some(project_path.to_string());
@@ -50,4 +56,24 @@ impl Project for NxProject {
 
         Ok(project)
     }
+
+    fn source_root(&self) -> Option<&str> {
+        self.source_root.as_deref()
+    }
+
+    fn implicit_dependencies(&self) -> Option<&Vec<String>> {
+        self.implicit_dependencies.as_ref()
+    }
+
+    fn tags(&self) -> Option<&Vec<String>> {
+        self.tags.as_ref()
+    }
+
+    fn named_inputs(&self) -> Option<&BTreeMap<String, Vec<Input>>> {
+        self.named_inputs.as_ref()
+    }
+
+    fn target_inputs(&self, target: &str) -> Option<&Vec<Input>> {
+        self.targets.as_ref()?.get(target)?.inputs.as_ref()
+    }
 }
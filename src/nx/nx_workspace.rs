@@ -0,0 +1,145 @@
+use crate::nx::nx_config::parse_entries;
+use crate::nx::NxProject;
+use crate::projects::Project;
+use anyhow::{Context, Result};
+use log::{debug, warn};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// The central configuration files, in the order of precedence
+const WORKSPACE_FILES: [&str; 2] = ["workspace.json", "angular.json"];
+
+/// A project entry of the `workspace.json` (Nx) or `angular.json` (Angular CLI) file,
+/// either a path to the directory with the `project.json` file or an inline configuration
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum ProjectEntry {
+    Path(String),
+    Inline(NxProject),
+}
+
+/// Projects defined by the `workspace.json` or `angular.json` file
+#[derive(Debug)]
+pub struct NxWorkspace {
+    pub projects: Vec<NxProject>,
+}
+
+impl NxWorkspace {
+    /// Loads the central project definitions, if any.
+    pub fn load(workspace_root: &Path) -> Result<Option<Self>> {
+        let Some(path) = WORKSPACE_FILES
+            .iter()
+            .map(|name| workspace_root.join(name))
+            .find(|path| path.is_file())
+        else {
+            return Ok(None);
+        };
+        debug!("Loading workspace projects from {:?}", path);
+        let contents =
+            fs::read_to_string(&path).with_context(|| format!("Could not read {:?}", path))?;
+        // the invalid entries are skipped instead of failing the whole workspace
+        let value: serde_json::Value = match serde_json::from_str(&contents) {
+            Ok(value) => value,
+            Err(err) => {
+                warn!("Skipping {:?}: {}", path, err);
+                return Ok(None);
+            }
+        };
+        let entries: BTreeMap<String, ProjectEntry> = parse_entries(&value, "projects", &path);
+
+        let mut projects = Vec::new();
+        for (name, entry) in entries {
+            let mut project = match entry {
+                ProjectEntry::Path(project_path) => {
+                    let project_path = normalize_root(&project_path);
+                    if !workspace_root
+                        .join(&project_path)
+                        .join("project.json")
+                        .is_file()
+                    {
+                        warn!(
+                            "Skipping project '{}': could not find '{}/project.json'",
+                            name, project_path
+                        );
+                        continue;
+                    }
+                    match NxProject::load(workspace_root, &project_path) {
+                        Ok(project) => project,
+                        Err(err) => {
+                            warn!("Skipping project '{}': {:#}", name, err);
+                            continue;
+                        }
+                    }
+                }
+                ProjectEntry::Inline(project) => project,
+            };
+            let Some(root) = project.root.as_deref().map(normalize_root) else {
+                warn!("Skipping project '{}': missing 'root'", name);
+                continue;
+            };
+            project.root = Some(root);
+            // the key is the name of the project
+            project.name.get_or_insert(name);
+            projects.push(project);
+        }
+        debug!(
+            "Workspace projects: {:?}",
+            projects
+                .iter()
+                .map(|project| project.name())
+                .collect::<Vec<_>>()
+        );
+
+        Ok(Some(NxWorkspace { projects }))
+    }
+}
+
+/// Turns `./apps/shop/` into `apps/shop`
+fn normalize_root(root: &str) -> String {
+    root.trim_start_matches("./")
+        .trim_end_matches('/')
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skips_invalid_projects() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("libs/ui")).unwrap();
+        fs::write(dir.path().join("libs/ui/project.json"), "{ invalid").unwrap();
+        fs::write(
+            dir.path().join("angular.json"),
+            r#"{
+                "version": 1,
+                "projects": {
+                    "shop": { "root": "./apps/shop/", "tags": ["scope:shop"] },
+                    "admin": { "root": "apps/admin", "tags": "scope:admin" },
+                    "api": { "tags": [] },
+                    "ui": "libs/ui",
+                    "missing": "libs/missing"
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let workspace = NxWorkspace::load(dir.path()).unwrap().unwrap();
+        let projects: Vec<_> = workspace
+            .projects
+            .iter()
+            .map(|project| (project.name(), project.root.as_deref()))
+            .collect();
+        assert_eq!(projects, [(Some("shop"), Some("apps/shop"))]);
+    }
+
+    #[test]
+    fn skips_invalid_file() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("workspace.json"), "{ \"projects\": ").unwrap();
+        assert!(NxWorkspace::load(dir.path()).unwrap().is_none());
+    }
+}
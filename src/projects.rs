@@ -1,28 +1,117 @@
-use anyhow::Result;
-use std::path::Path;
+use crate::cargo::CargoProject;
+use crate::go::GoProject;
+use crate::gradle::{GradleBuilds, GradleProject};
+use crate::graph::Dependency;
+use crate::maven::{MavenProject, MavenReactors};
+use crate::node::NodeProject;
+use crate::nx::{Input, NxProject};
+use crate::python::PythonProject;
+use anyhow::{bail, Result};
+use log::debug;
+use std::collections::BTreeMap;
+use std::path::{Component, Path};
 
 /// A trait for defining a project.
-pub trait Project {
+pub trait Project: std::fmt::Debug {
     fn name(&self) -> Option<&str>;
     fn load(workspace_root: &Path, project_path: &str) -> Result<Self>
     where
         Self: Sized;
+
+    /// The location of project's sources relative to the root of the workspace
+    fn source_root(&self) -> Option<&str> {
+        None
+    }
+
+    /// Names or glob patterns of the projects this project implicitly depends on
+    fn implicit_dependencies(&self) -> Option<&Vec<String>> {
+        None
+    }
+
+    /// Dependencies declared in the project manifest
+    fn dependencies(&self) -> Vec<Dependency> {
+        Vec::new()
+    }
+
+    /// Tags of the project, like `scope:shared`
+    fn tags(&self) -> Option<&Vec<String>> {
+        None
+    }
+
+    /// Named inputs of the project, overriding the ones from `nx.json`
+    fn named_inputs(&self) -> Option<&BTreeMap<String, Vec<Input>>> {
+        None
+    }
+
+    /// Inputs of the project target, overriding the `targetDefaults` from `nx.json`
+    fn target_inputs(&self, _target: &str) -> Option<&Vec<Input>> {
+        None
+    }
 }
 
-// pub fn get_project(workspace: &Workspace, project_path: &str) -> Result<Box<dyn Project>> {
-//     let project_root = workspace.root.join(project_path);
-//     let project_json_path = project_root.join("project.json");
-//     let package_json_path = project_root.join("package.json");
-//
-//     if project_json_path.is_file() {
-//         let nx_proj = nx::NxProject::load(workspace, project_path)?;
-//         debug!("{:?}", nx_proj);
-//         Ok(Box::new(nx_proj))
-//     } else if package_json_path.is_file() {
-//         let node_proj = node::NodeProject::load(workspace, project_path)?;
-//         debug!("{:?}", node_proj);
-//         Ok(Box::new(node_proj))
-//     } else {
-//         bail!("Could not find 'project.json' or 'package.json' in the project directory");
-//     }
-// }
+/// Loads the project located at `project_path`.
+///
+/// Nx projects (`project.json`) take precedence over Node projects (`package.json`),
+/// followed by Rust crates (`Cargo.toml`), Go modules (`go.mod`), Python packages (`pyproject.toml`),
+/// Maven modules (`pom.xml`) and Gradle projects (`build.gradle(.kts)` or `settings.gradle(.kts)`).
+/// The Maven reactors and the Gradle settings are shared by the projects of the workspace.
+pub fn get_project(
+    workspace_root: &Path,
+    project_path: &str,
+    maven_reactors: &mut MavenReactors,
+    gradle_builds: &mut GradleBuilds,
+) -> Result<Box<dyn Project>> {
+    let project_root = workspace_root.join(project_path);
+
+    if project_root.join("project.json").is_file() {
+        let nx_proj = NxProject::load(workspace_root, project_path)?;
+        debug!("{:?}", nx_proj);
+        Ok(Box::new(nx_proj))
+    } else if project_root.join("package.json").is_file() {
+        let node_proj = NodeProject::load(workspace_root, project_path)?;
+        debug!("{:?}", node_proj);
+        Ok(Box::new(node_proj))
+    } else if project_root.join("Cargo.toml").is_file() {
+        let cargo_proj = CargoProject::load(workspace_root, project_path)?;
+        debug!("{:?}", cargo_proj);
+        Ok(Box::new(cargo_proj))
+    } else if project_root.join("go.mod").is_file() {
+        let go_proj = GoProject::load(workspace_root, project_path)?;
+        debug!("{:?}", go_proj);
+        Ok(Box::new(go_proj))
+    } else if project_root.join("pyproject.toml").is_file() {
+        let python_proj = PythonProject::load(workspace_root, project_path)?;
+        debug!("{:?}", python_proj);
+        Ok(Box::new(python_proj))
+    } else if project_root.join("pom.xml").is_file() {
+        let reactor = maven_reactors.find(project_path)?;
+        let maven_proj = MavenProject::load_with_reactor(workspace_root, project_path, reactor)?;
+        debug!("{:?}", maven_proj);
+        Ok(Box::new(maven_proj))
+    } else if GradleProject::is_project(&project_root) {
+        let settings = gradle_builds.find(project_path)?;
+        let gradle_proj =
+            GradleProject::load_with_settings(workspace_root, project_path, settings)?;
+        debug!("{:?}", gradle_proj);
+        Ok(Box::new(gradle_proj))
+    } else {
+        bail!("Could not find a project manifest in the project directory");
+    }
+}
+
+/// Resolves the `relative` path against the `base` directory, both relative to the root of the workspace.
+/// Returns `None` for paths pointing outside the workspace.
+pub fn resolve_path(base: &str, relative: &str) -> Option<String> {
+    let mut components = Vec::new();
+    for component in Path::new(base).join(relative).components() {
+        match component {
+            Component::Normal(name) => components.push(name.to_string_lossy().to_string()),
+            Component::ParentDir => {
+                components.pop()?;
+            }
+            Component::CurDir => {}
+            Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    Some(components.join("/"))
+}
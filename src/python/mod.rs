@@ -0,0 +1,4 @@
+mod python_project;
+mod python_workspace;
+pub use python_project::PythonProject;
+pub use python_workspace::PythonWorkspace;
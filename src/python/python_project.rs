@@ -0,0 +1,353 @@
+use crate::graph::{Dependency, DependencyType};
+use crate::projects::{resolve_path, Project};
+use anyhow::{Context, Result};
+use log::debug;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// The `pyproject.toml` file
+#[derive(Debug, Default, Deserialize)]
+pub(super) struct PyProject {
+    pub project: Option<ProjectTable>,
+    #[serde(default)]
+    pub tool: Tool,
+    /// PEP 735 dependency groups, like `dev = ["pytest"]`
+    #[serde(default, rename = "dependency-groups")]
+    pub dependency_groups: BTreeMap<String, Vec<toml::Value>>,
+}
+
+/// The PEP 621 `[project]` table
+#[derive(Debug, Default, Deserialize)]
+pub(super) struct ProjectTable {
+    pub name: Option<String>,
+    #[serde(default)]
+    pub dependencies: Vec<String>,
+    #[serde(default, rename = "optional-dependencies")]
+    pub optional_dependencies: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub(super) struct Tool {
+    pub poetry: Option<Poetry>,
+    pub uv: Option<Uv>,
+}
+
+/// The `[tool.poetry]` table
+#[derive(Debug, Default, Deserialize)]
+pub(super) struct Poetry {
+    pub name: Option<String>,
+    #[serde(default)]
+    pub dependencies: BTreeMap<String, toml::Value>,
+    #[serde(default, rename = "dev-dependencies")]
+    pub dev_dependencies: BTreeMap<String, toml::Value>,
+    #[serde(default)]
+    pub group: BTreeMap<String, PoetryGroup>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub(super) struct PoetryGroup {
+    #[serde(default)]
+    pub dependencies: BTreeMap<String, toml::Value>,
+}
+
+/// The `[tool.uv]` table
+#[derive(Debug, Default, Deserialize)]
+pub(super) struct Uv {
+    #[serde(default)]
+    pub sources: BTreeMap<String, UvSource>,
+    pub workspace: Option<UvWorkspace>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub(super) struct UvSource {
+    pub path: Option<String>,
+    pub workspace: Option<bool>,
+}
+
+/// The `[tool.uv.workspace]` table
+#[derive(Debug, Default, Deserialize)]
+pub(super) struct UvWorkspace {
+    pub members: Option<Vec<String>>,
+    pub exclude: Option<Vec<String>>,
+}
+
+impl PyProject {
+    /// Reads the `pyproject.toml` file located in the `project_path` directory
+    pub fn load(workspace_root: &Path, project_path: &str) -> Result<Self> {
+        let path = workspace_root.join(project_path).join("pyproject.toml");
+        debug!("Loading project from {:?}", path);
+        let contents =
+            fs::read_to_string(&path).with_context(|| format!("Could not read {:?}", path))?;
+        toml::from_str(&contents).with_context(|| format!("Could not parse {:?}", path))
+    }
+
+    /// The PEP 621 name, or the Poetry one
+    pub fn name(&self) -> Option<&str> {
+        self.project
+            .as_ref()
+            .and_then(|project| project.name.as_deref())
+            .or_else(|| self.tool.poetry.as_ref()?.name.as_deref())
+    }
+}
+
+/// A struct representing a Python package defined by a `pyproject.toml` file
+#[derive(Debug)]
+pub struct PythonProject {
+    /// The name of the package
+    pub name: String,
+    /// Project's location relative to the root of the workspace
+    pub root: String,
+    /// Path dependencies and uv workspace dependencies on other packages of the repository
+    pub dependencies: Vec<Dependency>,
+}
+
+impl PythonProject {
+    /// Checks whether the directory contains a package,
+    /// `pyproject.toml` files only configuring tools or uv workspaces are not projects
+    pub fn is_package(path: &Path) -> bool {
+        let Ok(contents) = fs::read_to_string(path.join("pyproject.toml")) else {
+            return false;
+        };
+        match toml::from_str::<PyProject>(&contents) {
+            Ok(pyproject) => pyproject.name().is_some(),
+            // report the parsing errors when loading the project
+            Err(_) => true,
+        }
+    }
+}
+
+impl Project for PythonProject {
+    fn name(&self) -> Option<&str> {
+        Some(&self.name)
+    }
+
+    fn load(workspace_root: &Path, project_path: &str) -> Result<Self> {
+        let pyproject = PyProject::load(workspace_root, project_path)?;
+        let name = pyproject
+            .name()
+            .with_context(|| format!("'{}/pyproject.toml' has no project name", project_path))?
+            .to_string();
+
+        let mut requirements = Vec::new();
+        if let Some(project) = &pyproject.project {
+            requirements.extend(
+                project
+                    .dependencies
+                    .iter()
+                    .chain(project.optional_dependencies.values().flatten())
+                    .map(|requirement| (requirement.as_str(), DependencyType::Production)),
+            );
+        }
+        requirements.extend(
+            pyproject
+                .dependency_groups
+                .values()
+                .flatten()
+                .filter_map(toml::Value::as_str)
+                .map(|requirement| (requirement, DependencyType::Development)),
+        );
+
+        let mut dependencies = Vec::new();
+
+        // lib @ file:../lib
+        for (requirement, kind) in &requirements {
+            let Some((requirement_name, url)) = requirement.split_once('@') else {
+                continue;
+            };
+            if let Some(path) = file_url_path(workspace_root, project_path, url.trim()) {
+                dependencies.push(Dependency {
+                    name: requirement_name.trim().to_string(),
+                    path: Some(path),
+                    kind: *kind,
+                });
+            }
+        }
+
+        // [tool.uv.sources] lib = { path = "../lib" } or lib = { workspace = true }
+        if let Some(uv) = &pyproject.tool.uv {
+            for (source_name, source) in &uv.sources {
+                let kind = requirements
+                    .iter()
+                    .find(|(requirement, _)| {
+                        normalize_name(requirement_name(requirement)) == normalize_name(source_name)
+                    })
+                    .map_or(DependencyType::Production, |(_, kind)| *kind);
+                let path = match (&source.path, source.workspace) {
+                    (Some(path), _) => resolve_path(project_path, path),
+                    (None, Some(true)) => None,
+                    (None, _) => continue,
+                };
+                dependencies.push(Dependency {
+                    name: source_name.clone(),
+                    path,
+                    kind,
+                });
+            }
+        }
+
+        // [tool.poetry.dependencies] lib = { path = "../lib", develop = true }
+        if let Some(poetry) = &pyproject.tool.poetry {
+            let groups = [
+                (&poetry.dependencies, DependencyType::Production),
+                (&poetry.dev_dependencies, DependencyType::Development),
+            ]
+            .into_iter()
+            .chain(
+                poetry
+                    .group
+                    .values()
+                    .map(|group| (&group.dependencies, DependencyType::Development)),
+            );
+            for (group, kind) in groups {
+                for (dependency_name, spec) in group {
+                    let Some(path) = spec.get("path").and_then(toml::Value::as_str) else {
+                        continue;
+                    };
+                    if let Some(path) = resolve_path(project_path, path) {
+                        dependencies.push(Dependency {
+                            name: dependency_name.clone(),
+                            path: Some(path),
+                            kind,
+                        });
+                    }
+                }
+            }
+        }
+
+        Ok(PythonProject {
+            name,
+            root: project_path.to_string(),
+            dependencies,
+        })
+    }
+
+    fn dependencies(&self) -> Vec<Dependency> {
+        self.dependencies.clone()
+    }
+}
+
+/// Resolves `file:` URLs (`file:../lib`, `file:///${PROJECT_ROOT}/../lib`, `file:///abs/path`)
+/// to the directories relative to the root of the workspace
+fn file_url_path(workspace_root: &Path, project_path: &str, url: &str) -> Option<String> {
+    let path = url.strip_prefix("file:")?;
+    let path = path.strip_prefix("//").unwrap_or(path);
+
+    if let Some(relative) = path
+        .strip_prefix("/${PROJECT_ROOT}/")
+        .or_else(|| path.strip_prefix("${PROJECT_ROOT}/"))
+    {
+        return resolve_path(project_path, relative);
+    }
+    if Path::new(path).is_absolute() {
+        let relative = Path::new(path).strip_prefix(workspace_root).ok()?;
+        return resolve_path("", &relative.to_string_lossy());
+    }
+    resolve_path(project_path, path)
+}
+
+/// Returns the package name of a PEP 508 requirement, like `lib` for `lib[extra]>=1.0; python_version > "3.8"`
+fn requirement_name(requirement: &str) -> &str {
+    let end = requirement
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.'))
+        .unwrap_or(requirement.len());
+    &requirement[..end]
+}
+
+/// Normalizes package names as described by PEP 503
+fn normalize_name(name: &str) -> String {
+    name.to_lowercase().replace(['_', '.'], "-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_the_pep_621_or_the_poetry_name() {
+        let pyproject: PyProject = toml::from_str("[project]\nname = \"api\"\n").unwrap();
+        assert_eq!(pyproject.name(), Some("api"));
+
+        let pyproject: PyProject = toml::from_str("[tool.poetry]\nname = \"worker\"\n").unwrap();
+        assert_eq!(pyproject.name(), Some("worker"));
+
+        let pyproject: PyProject =
+            toml::from_str("[tool.uv.workspace]\nmembers = [\"packages/*\"]\n").unwrap();
+        assert_eq!(pyproject.name(), None);
+    }
+
+    #[test]
+    fn parses_dependency_tables() {
+        let pyproject: PyProject = toml::from_str(
+            r#"
+[project]
+name = "api"
+dependencies = ["shared", "lib @ file:///${PROJECT_ROOT}/../lib"]
+optional-dependencies = { test = ["pytest"] }
+
+[dependency-groups]
+dev = ["ruff", { include-group = "test" }]
+
+[tool.uv.sources]
+shared = { workspace = true }
+tools = { path = "../tools" }
+"#,
+        )
+        .unwrap();
+
+        let project = pyproject.project.as_ref().unwrap();
+        assert_eq!(project.dependencies.len(), 2);
+        assert_eq!(project.optional_dependencies["test"], ["pytest"]);
+        assert_eq!(pyproject.dependency_groups["dev"].len(), 2);
+        let sources = &pyproject.tool.uv.as_ref().unwrap().sources;
+        assert_eq!(sources["shared"].workspace, Some(true));
+        assert_eq!(sources["tools"].path.as_deref(), Some("../tools"));
+    }
+
+    #[test]
+    fn resolves_file_urls() {
+        let root = Path::new("/repo");
+        assert_eq!(
+            file_url_path(root, "services/api", "file:../lib").as_deref(),
+            Some("services/lib")
+        );
+        assert_eq!(
+            file_url_path(root, "services/api", "file:///${PROJECT_ROOT}/../lib").as_deref(),
+            Some("services/lib")
+        );
+        assert_eq!(
+            file_url_path(root, "services/api", "file:${PROJECT_ROOT}/vendor/lib").as_deref(),
+            Some("services/api/vendor/lib")
+        );
+        assert_eq!(
+            file_url_path(root, "services/api", "file:///repo/libs/core").as_deref(),
+            Some("libs/core")
+        );
+        assert_eq!(
+            file_url_path(root, "services/api", "file:///elsewhere/core"),
+            None
+        );
+        assert_eq!(
+            file_url_path(root, "services/api", "https://example.com/lib.whl"),
+            None
+        );
+    }
+
+    #[test]
+    fn extracts_requirement_names() {
+        assert_eq!(requirement_name("lib"), "lib");
+        assert_eq!(requirement_name("lib>=1.0"), "lib");
+        assert_eq!(
+            requirement_name("my_lib.core[extra]>=1.0; python_version > \"3.8\""),
+            "my_lib.core"
+        );
+        assert_eq!(requirement_name("lib @ file:../lib"), "lib");
+    }
+
+    #[test]
+    fn normalizes_names() {
+        assert_eq!(normalize_name("My_Lib.Core"), "my-lib-core");
+        assert_eq!(normalize_name("my-lib"), "my-lib");
+    }
+}
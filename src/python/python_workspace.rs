@@ -0,0 +1,70 @@
+use crate::python::python_project::PyProject;
+use anyhow::Result;
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use log::debug;
+use std::path::Path;
+
+/// Packages declared by the `[tool.uv.workspace]` table of a `pyproject.toml` file
+#[derive(Debug)]
+pub struct PythonWorkspace {
+    /// Workspace location relative to the root of the repository
+    pub root: String,
+    include: GlobSet,
+    exclude: GlobSet,
+}
+
+impl PythonWorkspace {
+    /// Loads the uv workspace declared in the `project_path` directory, if any.
+    pub fn load(workspace_root: &Path, project_path: &str) -> Result<Option<Self>> {
+        let pyproject = PyProject::load(workspace_root, project_path)?;
+        let Some(workspace) = pyproject.tool.uv.and_then(|uv| uv.workspace) else {
+            return Ok(None);
+        };
+        debug!(
+            "uv workspace '{}' members: {:?}, exclude: {:?}",
+            project_path, workspace.members, workspace.exclude
+        );
+
+        let mut include = GlobSetBuilder::new();
+        for pattern in workspace.members.iter().flatten() {
+            let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+            include.add(GlobBuilder::new(pattern).literal_separator(true).build()?);
+        }
+
+        let mut exclude = GlobSetBuilder::new();
+        for pattern in workspace.exclude.iter().flatten() {
+            let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+            exclude.add(GlobBuilder::new(pattern).literal_separator(true).build()?);
+        }
+
+        Ok(Some(PythonWorkspace {
+            root: project_path.to_string(),
+            include: include.build()?,
+            exclude: exclude.build()?,
+        }))
+    }
+
+    /// Finds the closest uv workspace containing the `project_path` directory, like uv does.
+    pub fn find(workspace_root: &Path, project_path: &str) -> Result<Option<Self>> {
+        for dir in Path::new(project_path).ancestors() {
+            if !workspace_root.join(dir).join("pyproject.toml").is_file() {
+                continue;
+            }
+            if let Some(workspace) = Self::load(workspace_root, &dir.to_string_lossy())? {
+                return Ok(Some(workspace));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Checks whether the package located at `project_path` belongs to this workspace
+    pub fn contains(&self, project_path: &str) -> bool {
+        let Ok(relative_path) = Path::new(project_path).strip_prefix(&self.root) else {
+            return false;
+        };
+        if relative_path.as_os_str().is_empty() {
+            return true;
+        }
+        self.include.is_match(relative_path) && !self.exclude.is_match(relative_path)
+    }
+}
@@ -1,3 +1,4 @@
+use crate::ci::CiEnvironment;
 use crate::workspace::Workspace;
 use crate::OutputFormat;
 use anyhow::Result;
@@ -6,14 +7,42 @@ use tabled::builder::Builder;
 use tabled::settings::Style;
 
 pub fn display_affected_files(workspace: &Workspace, format: &OutputFormat) -> Result<()> {
-    let file_paths = workspace.affected_files()?;
+    let files = workspace.affected_file_changes()?;
 
-    if file_paths.is_empty() {
+    if files.is_empty() {
         println!("No files affected");
         return Ok(());
     }
 
-    print_lines(&file_paths, format, "Path")?;
+    match format {
+        OutputFormat::Json => {
+            let json_output = serde_json::to_string_pretty(&files)?;
+            println!("{}", json_output);
+        }
+        OutputFormat::Table => {
+            let mut builder = Builder::default();
+            builder.push_record(["#", "Status", "Path", "Lines"]);
+
+            for (index, file) in files.iter().enumerate().map(|(i, file)| (i + 1, file)) {
+                let path = match &file.old_path {
+                    Some(old_path) => format!("{} -> {}", old_path, file.path),
+                    None => file.path.clone(),
+                };
+                let lines = format!("+{} -{}", file.additions, file.deletions);
+                builder.push_record([&index.to_string(), &file.status.to_string(), &path, &lines]);
+            }
+
+            let mut table = builder.build();
+            table.with(Style::modern());
+
+            println!("{}", table);
+        }
+        _ => {
+            for file in files {
+                println!("{}", file.path);
+            }
+        }
+    }
 
     Ok(())
 }
@@ -26,7 +55,90 @@ pub fn display_affected_projects(workspace: &Workspace, format: &OutputFormat) -
         return Ok(());
     }
 
-    print_lines(&projects, format, "Project")?;
+    let reasons = workspace.affected_reasons();
+    match format {
+        // the projects affected by global dependencies show the triggering files
+        OutputFormat::Table if !reasons.is_empty() => {
+            let mut builder = Builder::default();
+            builder.push_record(["#", "Project", "Reason"]);
+
+            for (index, project) in projects
+                .iter()
+                .enumerate()
+                .map(|(i, project)| (i + 1, project))
+            {
+                let reason = reasons
+                    .get(project)
+                    .map(|files| files.iter().cloned().collect::<Vec<_>>().join("\n"))
+                    .unwrap_or_default();
+                builder.push_record([&index.to_string(), project, &reason]);
+            }
+
+            let mut table = builder.build();
+            table.with(Style::modern());
+
+            println!("{}", table);
+        }
+        _ => print_lines(&projects, format, "Project")?,
+    }
+
+    Ok(())
+}
+
+pub fn display_affected_commits(workspace: &Workspace, format: &OutputFormat) -> Result<()> {
+    let commits = workspace.affected_commits()?;
+
+    if commits.is_empty() {
+        println!("No commits found");
+        return Ok(());
+    }
+
+    match format {
+        OutputFormat::Json => {
+            let json_output = serde_json::to_string_pretty(&commits)?;
+            println!("{}", json_output);
+        }
+        OutputFormat::Table => {
+            let mut builder = Builder::default();
+            builder.push_record(["#", "Commit", "Author", "Summary", "Files", "Projects"]);
+
+            for (index, commit) in commits
+                .iter()
+                .enumerate()
+                .map(|(i, commit)| (i + 1, commit))
+            {
+                builder.push_record([
+                    &index.to_string(),
+                    commit.short_sha(),
+                    &commit.author,
+                    &commit.summary,
+                    &commit.files.join("\n"),
+                    &commit.projects.join("\n"),
+                ]);
+            }
+
+            let mut table = builder.build();
+            table.with(Style::modern());
+
+            println!("{}", table);
+        }
+        _ => {
+            for commit in commits {
+                println!(
+                    "{} {} ({})",
+                    commit.short_sha(),
+                    commit.summary,
+                    commit.author
+                );
+                for file in &commit.files {
+                    println!("  {}", file);
+                }
+                if !commit.projects.is_empty() {
+                    println!("  Projects: {}", commit.projects.join(", "));
+                }
+            }
+        }
+    }
 
     Ok(())
 }
@@ -75,6 +187,41 @@ pub fn display_tasks(workspace: &Workspace, format: &OutputFormat) -> Result<()>
     Ok(())
 }
 
+pub fn display_ci_info(ci: Option<&CiEnvironment>, format: &OutputFormat) -> Result<()> {
+    let Some(ci) = ci else {
+        println!("No CI environment detected");
+        return Ok(());
+    };
+
+    match format {
+        OutputFormat::Json => {
+            let json_output = serde_json::to_string_pretty(ci)?;
+            println!("{}", json_output);
+        }
+        OutputFormat::Table => {
+            let mut builder = Builder::default();
+            builder.push_record(["Provider", "Base", "Head"]);
+            builder.push_record([
+                ci.provider.to_string(),
+                ci.base.clone().unwrap_or_default(),
+                ci.head.clone().unwrap_or_default(),
+            ]);
+
+            let mut table = builder.build();
+            table.with(Style::modern());
+
+            println!("{}", table);
+        }
+        _ => {
+            println!("provider: {}", ci.provider);
+            println!("base: {}", ci.base.as_deref().unwrap_or_default());
+            println!("head: {}", ci.head.as_deref().unwrap_or_default());
+        }
+    }
+
+    Ok(())
+}
+
 pub fn print_lines(lines: &HashSet<String>, format: &OutputFormat, header: &str) -> Result<()> {
     match format {
         OutputFormat::Json => {
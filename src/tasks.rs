@@ -1,4 +1,5 @@
 use crate::config::Task;
+use crate::files::FileStatus;
 use crate::workspace::Workspace;
 use anyhow::{bail, Context, Result};
 use globset::{Glob, GlobSetBuilder};
@@ -23,16 +24,30 @@ pub async fn run_tasks(workspace: &Workspace, pattern: &str) -> Result<()> {
 }
 
 async fn run_task(workspace: &Workspace, task: &Task) -> Result<()> {
-    let file_paths = workspace.affected_files()?;
-    let projects: Vec<String> = workspace.affected_projects()?.into_iter().collect();
-
-    // filter out files that exist on the filesystem
-    let file_paths: Vec<_> = file_paths
+    // the files and the projects of the task mode, e.g. the staged ones for a pre-commit task
+    let (files, projects) = match task.mode {
+        Some(mode) => (
+            workspace.affected_files_for_mode(mode)?,
+            workspace.affected_projects_for_mode(mode)?,
+        ),
+        None => (
+            workspace.affected_file_changes()?,
+            workspace.affected_projects()?,
+        ),
+    };
+    let projects: Vec<String> = projects.into_iter().collect();
+
+    // filter out deleted files and files that do not exist on the filesystem
+    let skip_deleted = task.skip_deleted.unwrap_or(true);
+    let files: Vec<_> = files
         .into_iter()
-        .filter(|path| workspace.root.join(path).exists())
+        .filter(|file| {
+            !skip_deleted
+                || (file.status != FileStatus::Deleted && workspace.root.join(&file.path).exists())
+        })
         .collect();
 
-    if file_paths.is_empty() {
+    if files.is_empty() {
         debug!("No files affected");
         return Ok(());
     }
@@ -49,31 +64,70 @@ async fn run_task(workspace: &Workspace, task: &Task) -> Result<()> {
 
     let patterns = builder.build()?;
 
-    let filtered_paths: Vec<_> = file_paths
+    let filtered_files: Vec<_> = files
         .into_iter()
-        .filter(|path| patterns.is_match(path))
+        .filter(|file| patterns.is_match(&file.path))
         .collect();
 
-    if filtered_paths.is_empty() {
+    if filtered_files.is_empty() {
         println!("No files matched the patterns");
         return Ok(());
     }
 
     debug!("Filtered files:");
-    for path in &filtered_paths {
-        debug!("- {}", path);
+    for file in &filtered_files {
+        debug!("- {}", file.path);
     }
 
     let separator = task.separator.as_deref().unwrap_or(" ");
-    let files = &filtered_paths.join(separator);
+    let files = &filtered_files
+        .iter()
+        .map(|file| file.path.as_str())
+        .collect::<Vec<_>>()
+        .join(separator);
+    let lines = &filtered_files
+        .iter()
+        .flat_map(|file| {
+            file.changed_lines()
+                .into_iter()
+                .map(move |range| format!("{}:{}", file.path, range))
+        })
+        .collect::<Vec<_>>()
+        .join(separator);
     let projects = &projects.join(separator);
 
+    // the JSON file is only written when a command refers to it,
+    // and is removed when the guard is dropped, including on error
+    let files_json_file = if task
+        .commands
+        .iter()
+        .any(|command| command.contains("{files_json}"))
+    {
+        let mut file = tempfile::Builder::new()
+            .prefix("affected-")
+            .suffix(".json")
+            .tempfile()
+            .context("Failed to create the affected files JSON")?;
+        serde_json::to_writer_pretty(&mut file, &filtered_files)
+            .context("Failed to write the affected files JSON")?;
+        debug!("Affected files JSON: {:?}", file.path());
+        Some(file)
+    } else {
+        None
+    };
+    let files_json = files_json_file
+        .as_ref()
+        .map(|file| file.path().to_string_lossy().to_string())
+        .unwrap_or_default();
+
     let mut handles = Vec::new();
 
     for command_template in &task.commands {
         let template = command_template.clone();
         let command_text = template
+            .replace("{files_json}", &files_json)
             .replace("{files}", files)
+            .replace("{lines}", lines)
             .replace("{projects}", projects);
         debug!("Running command: {}", &command_text);
 
@@ -107,10 +161,19 @@ async fn run_task(workspace: &Workspace, task: &Task) -> Result<()> {
         handles.push(handle);
     }
 
-    // Await all tasks and fail fast if any command fails
+    // Await all tasks before reporting the first failure,
+    // so the JSON file outlives every running command
+    let mut result = Ok(());
     for handle in handles {
-        handle.await.context("Task panicked")??;
+        let outcome = handle
+            .await
+            .context("Task panicked")
+            .and_then(|outcome| outcome);
+        if result.is_ok() {
+            result = outcome;
+        }
     }
+    drop(files_json_file);
 
-    Ok(())
+    result
 }
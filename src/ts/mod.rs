@@ -1,4 +1,5 @@
 pub mod resolver;
+#[allow(clippy::module_inception)]
 mod ts;
 
 pub use ts::*;
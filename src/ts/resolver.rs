@@ -41,12 +41,10 @@ fn find_in_node_modules(root_dir: &Path, module_name: &str) -> Option<PathBuf> {
         .standard_filters(true) // Respect .gitignore and .ignore
         .build();
 
-    for result in walker {
-        if let Ok(entry) = result {
-            let path = entry.path();
-            if path.ends_with(module_name) {
-                return Some(path.to_path_buf());
-            }
+    for entry in walker.flatten() {
+        let path = entry.path();
+        if path.ends_with(module_name) {
+            return Some(path.to_path_buf());
         }
     }
     None
@@ -67,7 +65,7 @@ pub fn demo() {
     ];
 
     for import in imports {
-        match resolve_module(&root_dir, &current_file, import) {
+        match resolve_module(&root_dir, current_file, import) {
             Some(resolved_path) => {
                 println!("Resolved '{}' to '{}'", import, resolved_path.display())
             }
@@ -132,28 +132,22 @@ pub fn execute_common_js() {
                 }
             }
             // Handle `require` calls
-            ModuleItem::Stmt(stmt) => {
-                if let swc_ecma_ast::Stmt::Decl(decl) = stmt {
-                    if let swc_ecma_ast::Decl::Var(var_decl) = decl {
-                        for decl in &var_decl.decls {
-                            if let Some(init) = &decl.init {
-                                if let Expr::Call(CallExpr { callee, args, .. }) = &**init {
-                                    // Match `callee` explicitly
-                                    if let Callee::Expr(expr) = callee {
-                                        if let Expr::Ident(ident) = &**expr {
-                                            if ident.sym == *"require" {
-                                                if let Some(arg) = args.get(0) {
-                                                    if let Expr::Lit(swc_ecma_ast::Lit::Str(
-                                                        module_specifier,
-                                                    )) = &*arg.expr
-                                                    {
-                                                        println!(
-                                                            "  - Require: {}",
-                                                            module_specifier.value
-                                                        );
-                                                    }
-                                                }
-                                            }
+            ModuleItem::Stmt(swc_ecma_ast::Stmt::Decl(swc_ecma_ast::Decl::Var(var_decl))) => {
+                for decl in &var_decl.decls {
+                    if let Some(init) = &decl.init {
+                        if let Expr::Call(CallExpr {
+                            callee: Callee::Expr(expr),
+                            args,
+                            ..
+                        }) = &**init
+                        {
+                            if let Expr::Ident(ident) = &**expr {
+                                if ident.sym == *"require" {
+                                    if let Some(arg) = args.first() {
+                                        if let Expr::Lit(swc_ecma_ast::Lit::Str(module_specifier)) =
+                                            &*arg.expr
+                                        {
+                                            println!("  - Require: {}", module_specifier.value);
                                         }
                                     }
                                 }
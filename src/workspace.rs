@@ -1,18 +1,37 @@
-use crate::config::Task;
-use crate::graph::{check_graph_recursively, NodeType, ProjectNode};
-use crate::nx::NxProject;
-use crate::projects::Project;
+use crate::cargo::{CargoProject, CargoWorkspace};
+use crate::commits::AffectedCommit;
+use crate::config::{DiffMode, Task};
+use crate::date::parse_timestamp;
+use crate::files::{AffectedFile, FileStatus, Hunk};
+use crate::go::GoWorkspace;
+use crate::gradle::{GradleBuilds, GradleProject};
+use crate::graph::{
+    check_graph_recursively, find_dependents, DependencyType, NodeType, ProjectNode,
+};
+use crate::maven::MavenReactors;
+use crate::node::{Lockfile, NodeWorkspace};
+use crate::nx::{Input, InputMatcher, NxConfig, NxWorkspace, ProjectInputs};
+use crate::projects::{get_project, Project};
+use crate::python::{PythonProject, PythonWorkspace};
 use crate::Config;
 use anyhow::{bail, Context, Result};
-use git2::{BranchType, DiffOptions, Repository};
-use globset::Glob;
+use git2::{
+    AutotagOption, Commit, Cred, CredentialType, Diff, DiffFindOptions, DiffOptions, Direction,
+    FetchOptions, FileMode, Oid, Patch, Remote, RemoteCallbacks, Repository, RevparseMode, Sort,
+    Tree,
+};
+use globset::{Glob, GlobBuilder};
 use ignore::WalkBuilder;
-use log::debug;
+use log::{debug, warn};
 use petgraph::Graph;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
 
-pub type WorkspaceGraph = Graph<NodeType, ()>;
+pub type WorkspaceGraph = Graph<NodeType, DependencyType>;
+
+/// The fetch depth libgit2 uses to fetch the complete history
+const UNSHALLOW_DEPTH: i32 = i32::MAX;
 
 #[derive(Default)]
 pub struct Workspace {
@@ -22,8 +41,10 @@ pub struct Workspace {
     repo: Option<Repository>,
     graph: Option<WorkspaceGraph>,
 
-    affected_files: Option<HashSet<String>>,
+    affected_files: Option<Vec<AffectedFile>>,
     affected_projects: Option<HashSet<String>>,
+    /// The global dependency files and the lockfiles that affected the projects
+    affected_reasons: Option<HashMap<String, BTreeSet<String>>>,
 }
 
 impl Workspace {
@@ -57,16 +78,21 @@ impl Workspace {
 
     /// Loads the repository
     pub async fn load(&mut self) -> Result<()> {
-        let repo = Repository::open(&self.root).context("Could not open the repository")?;
+        let mut repo = Repository::open(&self.root).context("Could not open the repository")?;
+
+        if let Some(config) = &self.config {
+            let fetch = config.fetch.unwrap_or(false);
+            if fetch {
+                fetch_base(&repo, config, None)?;
+            }
 
-        // TODO: introduce flag to fetch from remote
-        // Fetch the latest changes from the remote repository
-        // let mut remote = repo
-        //     .find_remote("origin")
-        //     .context("Could not find remote 'origin'")?;
-        // remote
-        //     .fetch(&["refs/heads/*:refs/remotes/origin/*"], None, None)
-        //     .context("Failed to fetch from remote repository")?;
+            if repo.is_shallow() {
+                debug!("Shallow clone detected");
+                if fetch {
+                    repo = deepen_history(repo, config)?;
+                }
+            }
+        }
 
         self.repo = Some(repo);
         self.build_projects_graph()?;
@@ -80,12 +106,21 @@ impl Workspace {
     /// Note that files that were removed are also included in the list.
     pub fn affected_files(&self) -> Result<HashSet<String>> {
         if let Some(files) = &self.affected_files {
-            Ok(files.clone())
+            Ok(files.iter().map(|file| file.path.clone()).collect())
         } else {
             Ok(HashSet::new())
         }
     }
 
+    /// Returns a list of affected files along with their change status, sorted by path.
+    pub fn affected_file_changes(&self) -> Result<Vec<AffectedFile>> {
+        if let Some(files) = &self.affected_files {
+            Ok(files.clone())
+        } else {
+            Ok(Vec::new())
+        }
+    }
+
     /// Returns a list of affected projects.
     ///
     /// The list is calculated based on the changes between the current branch and the base branch.
@@ -97,6 +132,108 @@ impl Workspace {
         }
     }
 
+    /// Returns the global dependency files that marked the projects as affected, by project name
+    pub fn affected_reasons(&self) -> HashMap<String, BTreeSet<String>> {
+        self.affected_reasons.clone().unwrap_or_default()
+    }
+
+    /// Calculates the list of affected files for a specific diff mode, sorted by path.
+    ///
+    /// Unlike [`Workspace::affected_file_changes`], the list is not cached
+    /// and the affected projects are not updated.
+    pub fn affected_files_for_mode(&self, mode: DiffMode) -> Result<Vec<AffectedFile>> {
+        find_affected_files(self, mode)
+    }
+
+    /// Returns the commits between the base and the head, newest first,
+    /// along with the affected files and projects each commit touched.
+    pub fn affected_commits(&self) -> Result<Vec<AffectedCommit>> {
+        let repo = self.repo.as_ref().expect("Repository not loaded");
+        let config = self.config.as_ref().expect("Configuration not loaded");
+        // both the old and the new paths of the renamed files, to list the source projects too
+        let affected_files: HashSet<String> = self
+            .affected_file_changes()?
+            .into_iter()
+            .flat_map(|file| std::iter::once(file.path).chain(file.old_path))
+            .collect();
+
+        let (base_commit, head_commit) = resolve_commit_range(repo, config)?;
+        let head_commit = match head_commit {
+            Some(commit) => commit,
+            None => repo.head()?.peel_to_commit()?,
+        };
+
+        let mut revwalk = repo.revwalk()?;
+        revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+        revwalk.push(head_commit.id())?;
+        if let Some(base_commit) = &base_commit {
+            revwalk.hide(base_commit.id())?;
+        }
+
+        let mut result = Vec::new();
+
+        for oid in revwalk {
+            let commit = repo.find_commit(oid?)?;
+            let parent_tree = match commit.parents().next() {
+                Some(parent) => Some(parent.tree()?),
+                None => None,
+            };
+
+            let mut diff_opts = DiffOptions::new();
+            let mut diff = repo.diff_tree_to_tree(
+                parent_tree.as_ref(),
+                Some(&commit.tree()?),
+                Some(&mut diff_opts),
+            )?;
+            find_similar(&mut diff, config, false)?;
+
+            let mut files = BTreeSet::new();
+            for delta in diff.deltas() {
+                for path in [delta.new_file().path(), delta.old_file().path()]
+                    .into_iter()
+                    .flatten()
+                {
+                    let path = path.to_string_lossy().to_string();
+                    if affected_files.contains(&path) {
+                        files.insert(path);
+                    }
+                }
+            }
+
+            let projects: BTreeSet<String> = files
+                .iter()
+                .filter_map(|file| self.owning_project(file))
+                .collect();
+
+            let author = commit.author();
+            result.push(AffectedCommit {
+                sha: commit.id().to_string(),
+                author: author.name().unwrap_or_default().to_string(),
+                email: author.email().unwrap_or_default().to_string(),
+                time: commit.time().seconds(),
+                summary: commit.summary().unwrap_or_default().to_string(),
+                files: files.into_iter().collect(),
+                projects: projects.into_iter().collect(),
+            });
+        }
+
+        Ok(result)
+    }
+
+    /// Returns the name of the project owning the given file
+    fn owning_project(&self, path: &str) -> Option<String> {
+        let graph = self.graph.as_ref()?;
+        find_owning_project(graph, path).map(|project| project.name.clone())
+    }
+
+    /// Returns the name of the workspace root directory
+    fn root_name(&self) -> String {
+        self.root
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| "root".to_string())
+    }
+
     /// Returns a list of tasks defined in the configuration
     pub fn tasks(&self) -> Vec<&Task> {
         let config = self.config.as_ref().expect("Configuration not loaded");
@@ -111,55 +248,414 @@ impl Workspace {
     }
 
     pub fn is_project_dir(path: &Path) -> bool {
-        path.is_dir()
-            && (
-                path.join("project.json").is_file() || path.join("package.json").is_file()
-                // || path.join("Cargo.toml").is_file()
-            )
+        Workspace::is_nx_project_dir(path)
+            || Workspace::is_node_project_dir(path)
+            || Workspace::is_cargo_project_dir(path)
+            || Workspace::is_go_project_dir(path)
+            || Workspace::is_python_project_dir(path)
+            || Workspace::is_maven_project_dir(path)
+            || Workspace::is_gradle_project_dir(path)
     }
 
     pub fn is_nx_project_dir(path: &Path) -> bool {
         path.is_dir() && path.join("project.json").is_file()
     }
 
+    pub fn is_node_project_dir(path: &Path) -> bool {
+        path.is_dir() && path.join("package.json").is_file()
+    }
+
+    pub fn is_go_project_dir(path: &Path) -> bool {
+        path.is_dir() && path.join("go.mod").is_file()
+    }
+
+    /// Checks whether the directory contains a Python package, tool-only `pyproject.toml` files are not projects
+    pub fn is_python_project_dir(path: &Path) -> bool {
+        path.is_dir() && PythonProject::is_package(path)
+    }
+
+    pub fn is_maven_project_dir(path: &Path) -> bool {
+        path.is_dir() && path.join("pom.xml").is_file()
+    }
+
+    pub fn is_gradle_project_dir(path: &Path) -> bool {
+        path.is_dir() && GradleProject::is_project(path)
+    }
+
+    /// Checks whether the directory contains a crate, virtual workspace manifests are not projects
+    pub fn is_cargo_project_dir(path: &Path) -> bool {
+        path.is_dir() && CargoProject::is_package(path)
+    }
+
     fn build_projects_graph(&mut self) -> Result<()> {
+        let mode = self
+            .config
+            .as_ref()
+            .and_then(|config| config.mode)
+            .unwrap_or_default();
+        let affected_files = find_affected_files(self, mode)?;
+        let affected = self.find_affected_projects(&affected_files, mode)?;
+        self.affected_files = Some(affected_files);
+
+        if let Some(affected) = affected {
+            self.graph = Some(affected.graph);
+            self.affected_projects = Some(affected.projects);
+            self.affected_reasons = Some(affected.reasons);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the projects affected by the changes of the diff mode, like `affected_projects`
+    /// does for the configured one, e.g. for the tasks with their own `mode`
+    pub fn affected_projects_for_mode(&self, mode: DiffMode) -> Result<HashSet<String>> {
+        let affected_files = find_affected_files(self, mode)?;
+        let affected = self.find_affected_projects(&affected_files, mode)?;
+        Ok(affected
+            .map(|affected| affected.projects)
+            .unwrap_or_default())
+    }
+
+    /// Builds the projects graph and finds the projects affected by the files,
+    /// `None` when there are no changes or no projects
+    fn find_affected_projects(
+        &self,
+        affected_files: &[AffectedFile],
+        mode: DiffMode,
+    ) -> Result<Option<AffectedProjects>> {
         let mut graph = WorkspaceGraph::new();
         let mut project_indices = HashMap::new();
+        let mut project_roots = HashMap::new();
 
-        // todo: support package.json
-        let projects = inspect_workspace(&self.root, Workspace::is_nx_project_dir)?;
-        if projects.is_empty() {
-            return Ok(());
+        if affected_files.is_empty() {
+            return Ok(None);
         }
 
-        let affected_files = find_affected_files(self)?;
-        if affected_files.is_empty() {
-            return Ok(());
+        // when workspace packages are declared, only they become Node projects
+        let node_workspace = NodeWorkspace::load(&self.root)?;
+        let workspace_root = self.root.clone();
+        // the multi-module builds are parsed once, both for the discovery and the projects
+        let mut maven_reactors = MavenReactors::new(&self.root);
+        let mut gradle_builds = GradleBuilds::new(&self.root);
+        let mut projects = inspect_workspace(&self.root, |path| {
+            if Workspace::is_nx_project_dir(path) {
+                return true;
+            }
+            let relative_path = path
+                .strip_prefix(&workspace_root)
+                .map(|relative_path| relative_path.to_string_lossy().to_string())
+                .unwrap_or_default();
+            if Workspace::is_node_project_dir(path)
+                && node_workspace
+                    .as_ref()
+                    .is_none_or(|node_workspace| node_workspace.contains(&relative_path))
+            {
+                return true;
+            }
+            // crates inside a Cargo workspace must match its `members` and not its `exclude` globs
+            if Workspace::is_cargo_project_dir(path) {
+                return match CargoWorkspace::find(&workspace_root, &relative_path) {
+                    Ok(Some(cargo_workspace)) => cargo_workspace.contains(&relative_path),
+                    // standalone crates, parsing errors are reported when loading the project
+                    Ok(None) | Err(_) => true,
+                };
+            }
+            // modules inside a Go workspace must be listed by its `use` directives
+            if Workspace::is_go_project_dir(path) {
+                return match GoWorkspace::find(&workspace_root, &relative_path) {
+                    Ok(Some(go_workspace)) => go_workspace.contains(&relative_path),
+                    Ok(None) | Err(_) => true,
+                };
+            }
+            // packages inside a uv workspace must match its `members` and not its `exclude` globs
+            if Workspace::is_python_project_dir(path) {
+                return match PythonWorkspace::find(&workspace_root, &relative_path) {
+                    Ok(Some(python_workspace)) => python_workspace.contains(&relative_path),
+                    Ok(None) | Err(_) => true,
+                };
+            }
+            // modules inside a Maven reactor must be listed by the aggregator `<modules>`
+            if Workspace::is_maven_project_dir(path) {
+                return match maven_reactors.find(&relative_path) {
+                    Ok(Some(reactor)) => reactor.contains(&relative_path),
+                    Ok(None) | Err(_) => true,
+                };
+            }
+            // projects inside a Gradle build must be included by its settings file
+            if Workspace::is_gradle_project_dir(path) {
+                return match gradle_builds.find(&relative_path) {
+                    Ok(Some(settings)) => settings.contains(&relative_path),
+                    Ok(None) | Err(_) => true,
+                };
+            }
+            false
+        })?;
+        // the root project only owns the files that do not belong to other projects
+        if Workspace::is_project_dir(&self.root) {
+            projects.insert(String::new());
+        }
+        // projects defined centrally by `workspace.json` or `angular.json` take precedence
+        let mut central_projects: HashMap<String, Box<dyn Project>> = HashMap::new();
+        if let Some(nx_workspace) = NxWorkspace::load(&self.root)? {
+            for project in nx_workspace.projects {
+                let project_path = project.root.clone().unwrap_or_default();
+                projects.insert(project_path.clone());
+                central_projects.insert(project_path, Box::new(project));
+            }
+        }
+        if projects.is_empty() {
+            return Ok(None);
         }
 
+        let nx_config = NxConfig::load(&self.root)?;
+        let config = self.config.as_ref().expect("Configuration not loaded");
+        let mut project_inputs_by_name = HashMap::new();
+        // the `^` inputs of the dependents are resolved with the named inputs of the dependencies
+        let mut named_inputs_by_name = HashMap::new();
+        let mut named_input_defined = config.named_input.as_ref().is_none_or(|named_input| {
+            nx_config
+                .as_ref()
+                .map_or(named_input == "default", |nx_config| {
+                    nx_config.has_named_input(named_input)
+                })
+        });
+
         // todo: insert file nodes into the graph
 
-        let mut affected_projects = HashSet::new();
+        for project_path in &projects {
+            debug!("Project: {:?}", project_path);
+            let project = match central_projects.remove(project_path).map_or_else(
+                || {
+                    get_project(
+                        &self.root,
+                        project_path,
+                        &mut maven_reactors,
+                        &mut gradle_builds,
+                    )
+                },
+                Ok,
+            ) {
+                Ok(project) => project,
+                Err(err) => {
+                    warn!("Skipping project '{}': {:#}", project_path, err);
+                    continue;
+                }
+            };
+            let project_name = match project.name() {
+                Some(name) => name.to_string(),
+                None if project_path.is_empty() => self.root_name(),
+                None => project_path.clone(),
+            };
 
-        for project in &projects {
-            debug!("Project: {:?}", project);
-            // todo: support package.json
-            let nx_project = NxProject::load(&self.root, project)?;
-            let project_name = nx_project.name().unwrap_or("Unnamed");
-            let project_root = nx_project.source_root.clone();
+            if let Some(inputs) =
+                project_inputs(config, nx_config.as_ref(), project.as_ref(), &project_name)?
+            {
+                project_inputs_by_name.insert(project_name.clone(), inputs);
+            }
+            if let Some(named_inputs) = project.named_inputs() {
+                named_inputs_by_name.insert(project_name.clone(), named_inputs.clone());
+            }
+            if let (Some(named_input), Some(named_inputs)) =
+                (&config.named_input, project.named_inputs())
+            {
+                named_input_defined |= named_inputs.contains_key(named_input);
+            }
 
             let project_node = graph.add_node(NodeType::Project(ProjectNode {
-                name: project_name.to_string(),
-                path: project_root,
-                implicit_dependencies: nx_project.implicit_dependencies.clone(),
+                name: project_name.clone(),
+                root: project_path.clone(),
+                path: project.source_root().map(str::to_string),
+                implicit_dependencies: project.implicit_dependencies().cloned(),
+                tags: project.tags().cloned().unwrap_or_default(),
+                dependencies: project.dependencies(),
             }));
 
-            project_indices.insert(project_name.to_string(), project_node);
+            project_indices.insert(project_name, project_node);
+            project_roots.insert(project_path.clone(), project_node);
+        }
+
+        if !named_input_defined {
+            bail!(
+                "The named input '{}' is not defined in 'nx.json' or in the projects",
+                config.named_input.as_deref().unwrap_or_default()
+            );
+        }
+        let uses_inputs =
+            config.named_input.is_some() || config.target.is_some() || config.inputs.is_some();
 
-            // find affected projects
-            for file in &affected_files {
-                if file.starts_with(project) {
-                    affected_projects.insert(project_name.to_string());
+        // `*` does not match the nested files, like in `.gitignore`
+        let global_dependencies = config
+            .global_dependencies
+            .iter()
+            .flatten()
+            .map(|dependency| {
+                Ok((
+                    GlobBuilder::new(dependency.pattern())
+                        .literal_separator(true)
+                        .build()?
+                        .compile_matcher(),
+                    dependency.projects(),
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        // global implicit dependencies from `nx.json`, like `"package.json": "*"`,
+        // with the names of the projects they affect
+        let implicit_dependencies = match &nx_config {
+            Some(nx_config) => nx_config.implicit_dependency_matchers()?,
+            None => Vec::new(),
+        }
+        .into_iter()
+        .map(|matcher| {
+            let mut projects = HashSet::new();
+            for pattern in &matcher.projects {
+                let glob = Glob::new(pattern)?.compile_matcher();
+                projects.extend(
+                    project_indices
+                        .keys()
+                        .filter(|name| pattern == "*" || *name == pattern || glob.is_match(name))
+                        .cloned(),
+                );
+            }
+            Ok((matcher, projects))
+        })
+        .collect::<Result<Vec<_>>>()?;
+        let mut affected_reasons: HashMap<String, BTreeSet<String>> = HashMap::new();
+
+        // the lockfiles affect only the projects depending on the changed packages,
+        // unless they are listed in the global or the `nx.json` implicit dependencies
+        let mut lockfiles = Vec::new();
+        for file in affected_files {
+            if !Lockfile::is_lockfile(&file.path)
+                || global_dependencies
+                    .iter()
+                    .any(|(glob, _)| glob.is_match(&file.path))
+                || implicit_dependencies.iter().any(|(matcher, _)| {
+                    !matcher.projects.is_empty() && matcher.is_match(&file.path)
+                })
+            {
+                continue;
+            }
+            lockfiles.push(file);
+        }
+        let mut lockfile_projects: HashMap<&str, HashSet<String>> = HashMap::new();
+        if !lockfiles.is_empty() {
+            // both versions of the lockfiles are read from the same commits as the diff
+            let repo = self.repo.as_ref().expect("Repository not loaded");
+            let (base_commit, head_commit) = resolve_commit_range(repo, config)?;
+            for file in lockfiles {
+                let packages = match find_changed_packages(
+                    self,
+                    mode,
+                    base_commit.as_ref(),
+                    head_commit.as_ref(),
+                    file,
+                ) {
+                    Ok(packages) => packages,
+                    Err(err) => {
+                        warn!("Could not compare the lockfile '{}': {:#}", file.path, err);
+                        continue;
+                    }
+                };
+                debug!("Changed packages in '{}': {:?}", file.path, packages);
+                let dir = file.path.rsplit_once('/').map_or("", |(dir, _)| dir);
+                let projects = graph
+                    .node_weights()
+                    .filter_map(|node| match node {
+                        NodeType::Project(project) => Some(project),
+                        _ => None,
+                    })
+                    .filter(|project| {
+                        dir.is_empty()
+                            || project.root == dir
+                            || project.root.starts_with(&format!("{}/", dir))
+                    })
+                    .filter(|project| {
+                        project.dependencies.iter().any(|dependency| {
+                            dependency.path.is_none() && packages.contains(&dependency.name)
+                        })
+                    })
+                    .map(|project| project.name.clone())
+                    .collect();
+                lockfile_projects.insert(&file.path, projects);
+            }
+        }
+
+        // find affected projects
+        // renamed files affect both the old and the new owning projects
+        let mut affected_projects = HashSet::new();
+        // with the inputs configured, these projects affect all their dependents
+        let mut propagating_projects = HashSet::new();
+        // and the other changes are matched against the `^` inputs of the dependents
+        let mut owned_changes: HashMap<String, (String, Vec<String>)> = HashMap::new();
+        for file in affected_files {
+            if let Some(projects) = lockfile_projects.get(file.path.as_str()) {
+                for name in projects {
+                    debug!("Lockfile '{}' affects '{}'", file.path, name);
+                    affected_projects.insert(name.clone());
+                    propagating_projects.insert(name.clone());
+                    affected_reasons
+                        .entry(name.clone())
+                        .or_default()
+                        .insert(file.path.clone());
+                }
+                continue;
+            }
+
+            for path in std::iter::once(&file.path).chain(file.old_path.as_ref()) {
+                let owner = find_owning_project(&graph, path);
+                if uses_inputs {
+                    if let Some(project) = owner {
+                        owned_changes
+                            .entry(project.name.clone())
+                            .or_insert_with(|| (project.root.clone(), Vec::new()))
+                            .1
+                            .push(path.clone());
+                    }
+                    // the `{workspaceRoot}` inputs may affect the projects not owning the file
+                    for node in graph.node_weights() {
+                        let NodeType::Project(project) = node else {
+                            continue;
+                        };
+                        let owned = owner.is_some_and(|owner| owner.name == project.name);
+                        let matched = match project_inputs_by_name.get(&project.name) {
+                            Some(inputs) => inputs.own.matches(path, &project.root, owned),
+                            None => owned,
+                        };
+                        if matched {
+                            affected_projects.insert(project.name.clone());
+                        }
+                    }
+                } else {
+                    affected_projects.extend(owner.map(|project| project.name.clone()));
+                }
+
+                // global implicit dependencies from `nx.json`
+                for (matcher, projects) in &implicit_dependencies {
+                    if matcher.is_match(path) {
+                        affected_projects.extend(projects.iter().cloned());
+                        propagating_projects.extend(projects.iter().cloned());
+                    }
+                }
+
+                // global dependencies from the configuration, like `tsconfig.base.json`
+                for (glob, patterns) in &global_dependencies {
+                    if !glob.is_match(path) {
+                        continue;
+                    }
+                    for node in graph.node_weights() {
+                        let NodeType::Project(project) = node else {
+                            continue;
+                        };
+                        if patterns.is_none_or(|patterns| matches_project(patterns, project)) {
+                            debug!("Global dependency '{}' affects '{}'", path, project.name);
+                            affected_projects.insert(project.name.clone());
+                            propagating_projects.insert(project.name.clone());
+                            affected_reasons
+                                .entry(project.name.clone())
+                                .or_default()
+                                .insert(path.clone());
+                        }
+                    }
                 }
             }
         }
@@ -173,14 +669,14 @@ impl Workspace {
                 if let Some(dependencies) = &project_node.implicit_dependencies {
                     for dependency in dependencies {
                         if let Some(dependency_node) = project_indices.get(dependency) {
-                            graph.add_edge(node_index, *dependency_node, ());
+                            graph.add_edge(node_index, *dependency_node, DependencyType::Implicit);
                             // println!("{} -> {}", project_node.name, dependency);
                         } else {
                             let glob = Glob::new(dependency)?.compile_matcher();
                             // for example: "shop-*" -> "shop-admin"
                             for (name, index) in &project_indices {
                                 if glob.is_match(name) {
-                                    graph.add_edge(node_index, *index, ());
+                                    graph.add_edge(node_index, *index, DependencyType::Implicit);
                                     // println!("{} -> {}", project_node.name, name);
                                 }
                             }
@@ -190,17 +686,144 @@ impl Workspace {
             }
         }
 
-        let all_affected_projects = check_graph_recursively(&graph, &affected_projects);
+        // update the graph with the dependencies declared in project manifests
 
-        self.graph = Some(graph);
-        self.affected_files = Some(affected_files);
-        self.affected_projects = Some(all_affected_projects);
+        for node_index in graph.node_indices() {
+            let node = graph[node_index].clone();
 
-        Ok(())
+            if let NodeType::Project(project_node) = node {
+                for dependency in &project_node.dependencies {
+                    let dependency_node = match &dependency.path {
+                        Some(path) => project_roots.get(path),
+                        None => project_indices.get(&dependency.name),
+                    };
+                    if let Some(dependency_node) = dependency_node {
+                        if *dependency_node != node_index
+                            && graph.find_edge(node_index, *dependency_node).is_none()
+                        {
+                            graph.add_edge(node_index, *dependency_node, dependency.kind);
+                        }
+                    }
+                }
+            }
+        }
+
+        let all_affected_projects = if uses_inputs {
+            let default_nx_config = NxConfig::default();
+            let nx_config = nx_config.as_ref().unwrap_or(&default_nx_config);
+            let mut projects = find_dependents(&graph, &propagating_projects);
+            // the dependents are affected by the changes matching their `^` inputs only,
+            // expanded with the named inputs of the changed dependency like in Nx,
+            // and all the changes count for the dependents without inputs
+            for (owner, (owner_root, paths)) in &owned_changes {
+                for dependent in find_dependents(&graph, &HashSet::from([owner.clone()])) {
+                    let matched = match project_inputs_by_name.get(&dependent) {
+                        Some(inputs) => {
+                            let patterns = nx_config.dependency_patterns(
+                                &inputs.dependencies,
+                                named_inputs_by_name.get(owner),
+                            );
+                            let matcher = InputMatcher::new(&patterns)?;
+                            paths
+                                .iter()
+                                .any(|path| matcher.matches(path, owner_root, true))
+                        }
+                        None => true,
+                    };
+                    if matched {
+                        debug!("'{}' affects the dependent '{}'", owner, dependent);
+                        projects.insert(dependent);
+                    }
+                }
+            }
+            projects.extend(affected_projects);
+            projects
+        } else {
+            check_graph_recursively(&graph, &affected_projects)
+        };
+
+        Ok(Some(AffectedProjects {
+            graph,
+            projects: all_affected_projects,
+            reasons: affected_reasons,
+        }))
     }
 }
 
-fn find_affected_files(workspace: &Workspace) -> Result<HashSet<String>> {
+/// The projects graph and the affected projects for a set of changes
+struct AffectedProjects {
+    graph: WorkspaceGraph,
+    projects: HashSet<String>,
+    /// The global dependency files and the lockfiles that affected the projects
+    reasons: HashMap<String, BTreeSet<String>>,
+}
+
+/// Checks whether the project matches any of the name or `tag:` glob patterns
+fn matches_project(patterns: &[String], project: &ProjectNode) -> bool {
+    patterns.iter().any(|pattern| {
+        let (values, pattern) = match pattern.strip_prefix("tag:") {
+            Some(tag) => (project.tags.as_slice(), tag),
+            None => (std::slice::from_ref(&project.name), pattern.as_str()),
+        };
+        Glob::new(pattern).is_ok_and(|glob| {
+            let matcher = glob.compile_matcher();
+            values.iter().any(|value| matcher.is_match(value))
+        })
+    })
+}
+
+/// Returns the inputs of the project: the `inputs` override from the configuration,
+/// or the inputs of the target or the named input from Nx, if configured
+fn project_inputs(
+    config: &Config,
+    nx_config: Option<&NxConfig>,
+    project: &dyn Project,
+    project_name: &str,
+) -> Result<Option<ProjectInputs>> {
+    let default_config = NxConfig::default();
+    let nx_config = nx_config.unwrap_or(&default_config);
+
+    let overrides = config.inputs.as_ref().and_then(|inputs| {
+        inputs.get(project_name).or_else(|| {
+            inputs
+                .iter()
+                .find(|(pattern, _)| {
+                    Glob::new(pattern)
+                        .is_ok_and(|glob| glob.compile_matcher().is_match(project_name))
+                })
+                .map(|(_, inputs)| inputs)
+        })
+    });
+
+    let inputs = if let Some(overrides) = overrides {
+        overrides.iter().cloned().map(Input::Value).collect()
+    } else if let Some(target) = &config.target {
+        project
+            .target_inputs(target)
+            .or_else(|| nx_config.target_inputs(target))
+            .cloned()
+            // all the files of the project and of its dependencies, like in Nx
+            .unwrap_or_else(|| {
+                vec![
+                    Input::Value("default".to_string()),
+                    Input::Value("^default".to_string()),
+                ]
+            })
+    } else if let Some(named_input) = &config.named_input {
+        vec![
+            Input::Value(named_input.clone()),
+            Input::Value(format!("^{}", named_input)),
+        ]
+    } else {
+        return Ok(None);
+    };
+
+    nx_config
+        .project_inputs(&inputs, project.named_inputs())
+        .map(Some)
+}
+
+fn find_affected_files(workspace: &Workspace, mode: DiffMode) -> Result<Vec<AffectedFile>> {
     let repo = workspace.repo.as_ref().expect("Repository not loaded");
     let config = workspace.config.as_ref().expect("Configuration not loaded");
 
@@ -211,65 +834,761 @@ fn find_affected_files(workspace: &Workspace) -> Result<HashSet<String>> {
         .ok_or_else(|| anyhow::anyhow!("Could not determine current branch"))?;
     debug!("Current branch: {}", current_branch);
 
-    // Get the OIDs (object IDs) for the current branch and the main branch
-    // let current_oid = head.target().context("Could not get current branch OID")?;
-    // debug!("Current OID: {}", current_oid);
+    let (base_commit, head_commit) = resolve_commit_range(repo, config).map_err(|err| {
+        if repo.is_shallow() {
+            err.context(
+                "The repository is a shallow clone, so the base commit or the merge base \
+                may be missing from the history. Fetch more history (e.g. `git fetch --deepen=100`), \
+                or use --fetch to deepen the history automatically",
+            )
+        } else {
+            err
+        }
+    })?;
+    // Get the trees for each commit, the missing base stands for the empty tree
+    let base_tree = match &base_commit {
+        Some(base_commit) => {
+            debug!("Base OID: {}", base_commit.id());
+            Some(base_commit.tree()?)
+        }
+        None => {
+            debug!("Base: empty tree");
+            None
+        }
+    };
+
+    // Compare the trees to get the diff
+    let mut diff_opts = diff_options(config);
+
+    debug!("Diff mode: {:?}", mode);
+    let mut diff = match (mode, &head_commit) {
+        (DiffMode::Staged | DiffMode::Unstaged, Some(_)) => {
+            bail!("The staged and unstaged modes cannot be used with a head revision")
+        }
+        (DiffMode::All | DiffMode::Committed, Some(head_commit)) => {
+            debug!("Head OID: {}", head_commit.id());
+            let head_tree = head_commit.tree()?;
+            repo.diff_tree_to_tree(base_tree.as_ref(), Some(&head_tree), Some(&mut diff_opts))?
+        }
+        (DiffMode::Committed, None) => {
+            let head_commit = head.peel_to_commit()?;
+            debug!("Head OID: {}", head_commit.id());
+            let head_tree = head_commit.tree()?;
+            repo.diff_tree_to_tree(base_tree.as_ref(), Some(&head_tree), Some(&mut diff_opts))?
+        }
+        (DiffMode::Staged, None) => {
+            debug!("Head: index");
+            repo.diff_tree_to_index(base_tree.as_ref(), None, Some(&mut diff_opts))?
+        }
+        (DiffMode::Unstaged, None) => {
+            debug!("Head: working directory (unstaged changes)");
+            repo.diff_index_to_workdir(None, Some(&mut diff_opts))?
+        }
+        (DiffMode::All, None) => {
+            debug!("Head: working directory");
+            repo.diff_tree_to_workdir_with_index(base_tree.as_ref(), Some(&mut diff_opts))?
+        }
+    };
+
+    let workdir = head_commit.is_none() && matches!(mode, DiffMode::All | DiffMode::Unstaged);
+    find_similar(&mut diff, config, workdir)?;
+
+    let mut result = Vec::new();
+    collect_affected_files(repo, &diff, config, workdir, "", &mut result)?;
+
+    result.sort_by(|a, b| a.path.cmp(&b.path));
+
+    Ok(result)
+}
+
+fn diff_options(config: &Config) -> DiffOptions {
+    let mut diff_opts = DiffOptions::new();
+    // hunks should cover the changed lines only
+    diff_opts.context_lines(0);
+    if config.untracked.unwrap_or(true) {
+        diff_opts
+            .include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .show_untracked_content(true);
+    }
+    diff_opts
+}
+
+/// Detects renamed and copied files
+fn find_similar(diff: &mut Diff, config: &Config, workdir: bool) -> Result<()> {
+    let similarity = config.similarity.unwrap_or(50);
+    let mut find_opts = DiffFindOptions::new();
+    find_opts
+        .renames(true)
+        .copies(true)
+        .for_untracked(workdir)
+        .rename_threshold(similarity)
+        .copy_threshold(similarity);
+    diff.find_similar(Some(&mut find_opts))?;
+
+    Ok(())
+}
+
+/// Converts the diff deltas to affected files, prefixing the paths with `prefix`.
+///
+/// When submodule recursion is enabled, changed submodules are replaced
+/// with the files changed inside them.
+fn collect_affected_files(
+    repo: &Repository,
+    diff: &Diff,
+    config: &Config,
+    workdir: bool,
+    prefix: &str,
+    result: &mut Vec<AffectedFile>,
+) -> Result<()> {
+    let recurse_submodules = config.submodules.unwrap_or(false);
+
+    for (index, delta) in diff.deltas().enumerate() {
+        let Some(status) = FileStatus::from_delta(delta.status()) else {
+            continue;
+        };
+        let Some(path) = delta.new_file().path().or(delta.old_file().path()) else {
+            continue;
+        };
+        let path = path.to_string_lossy().to_string();
+
+        let is_submodule = delta.old_file().mode() == FileMode::Commit
+            || delta.new_file().mode() == FileMode::Commit;
+        if recurse_submodules && is_submodule {
+            let submodule_prefix = format!("{}{}/", prefix, path);
+            match collect_submodule_files(
+                repo,
+                &path,
+                delta.old_file().id(),
+                delta.new_file().id(),
+                config,
+                workdir,
+                &submodule_prefix,
+                result,
+            ) {
+                Ok(()) => continue,
+                Err(err) => warn!(
+                    "Could not inspect submodule '{}{}': {:#}",
+                    prefix, path, err
+                ),
+            }
+        }
+
+        let old_path = delta
+            .old_file()
+            .path()
+            .map(|old_path| old_path.to_string_lossy().to_string())
+            .filter(|old_path| *old_path != path)
+            .map(|old_path| format!("{}{}", prefix, old_path));
+
+        let mut hunks = Vec::new();
+        let (mut additions, mut deletions) = (0, 0);
+        if let Some(patch) = Patch::from_diff(diff, index)? {
+            (_, additions, deletions) = patch.line_stats()?;
+            for hunk_index in 0..patch.num_hunks() {
+                let (hunk, _) = patch.hunk(hunk_index)?;
+                hunks.push(Hunk {
+                    old_start: hunk.old_start(),
+                    old_lines: hunk.old_lines(),
+                    new_start: hunk.new_start(),
+                    new_lines: hunk.new_lines(),
+                });
+            }
+        }
+
+        result.push(AffectedFile {
+            path: format!("{}{}", prefix, path),
+            old_path,
+            status,
+            additions,
+            deletions,
+            hunks,
+        });
+    }
+
+    Ok(())
+}
+
+/// Collects the files changed inside a submodule between the old and new commits it points to.
+/// For working directory diffs, the submodule's own working directory is used as the new side.
+#[allow(clippy::too_many_arguments)]
+fn collect_submodule_files(
+    repo: &Repository,
+    path: &str,
+    old_id: Oid,
+    new_id: Oid,
+    config: &Config,
+    workdir: bool,
+    prefix: &str,
+    result: &mut Vec<AffectedFile>,
+) -> Result<()> {
+    let submodule_repo = repo.find_submodule(path)?.open()?;
+    debug!("Inspecting submodule: {}", prefix);
 
-    let base: Option<&str> = config.base.as_deref();
+    let find_tree = |id: Oid| -> Result<Option<Tree>> {
+        if id.is_zero() {
+            Ok(None)
+        } else {
+            Ok(Some(submodule_repo.find_commit(id)?.tree()?))
+        }
+    };
+
+    let old_tree = find_tree(old_id)?;
+    let mut diff_opts = diff_options(config);
+    let mut diff = if workdir {
+        submodule_repo.diff_tree_to_workdir_with_index(old_tree.as_ref(), Some(&mut diff_opts))?
+    } else {
+        let new_tree = find_tree(new_id)?;
+        submodule_repo.diff_tree_to_tree(
+            old_tree.as_ref(),
+            new_tree.as_ref(),
+            Some(&mut diff_opts),
+        )?
+    };
+    find_similar(&mut diff, config, workdir)?;
+
+    collect_affected_files(&submodule_repo, &diff, config, workdir, prefix, result)
+}
+
+/// Returns the external packages whose versions changed in the lockfile,
+/// along with the packages depending on them
+fn find_changed_packages(
+    workspace: &Workspace,
+    mode: DiffMode,
+    base_commit: Option<&Commit>,
+    head_commit: Option<&Commit>,
+    file: &AffectedFile,
+) -> Result<HashSet<String>> {
+    let repo = workspace.repo.as_ref().expect("Repository not loaded");
+    let old_path = file.old_path.as_ref().unwrap_or(&file.path);
+
+    // the unstaged changes are compared against the index
+    let base = match (mode, head_commit, base_commit) {
+        (DiffMode::Unstaged, None, _) => read_index_file(repo, old_path)?,
+        (_, _, Some(base_commit)) => read_tree_file(repo, &base_commit.tree()?, old_path)?,
+        (_, _, None) => None,
+    };
+    let head = match (mode, head_commit) {
+        (_, Some(head_commit)) => read_tree_file(repo, &head_commit.tree()?, &file.path)?,
+        (DiffMode::Committed, None) => {
+            let head_tree = repo.head()?.peel_to_tree()?;
+            read_tree_file(repo, &head_tree, &file.path)?
+        }
+        (DiffMode::Staged, None) => read_index_file(repo, &file.path)?,
+        (DiffMode::Unstaged | DiffMode::All, None) => {
+            std::fs::read_to_string(workspace.root.join(&file.path)).ok()
+        }
+    };
+
+    let parse = |contents: Option<String>| match contents {
+        Some(contents) => Lockfile::parse(&file.path, &contents),
+        None => Ok(Lockfile::default()),
+    };
+    let base = parse(base)?;
+    let head = parse(head)?;
+
+    let changed = Lockfile::changed_packages(&base, &head);
+    let mut packages = base.dependents(&changed);
+    packages.extend(head.dependents(&changed));
+    Ok(packages)
+}
+
+/// Reads the file from the tree, `None` if the file does not exist
+fn read_tree_file(repo: &Repository, tree: &Tree, path: &str) -> Result<Option<String>> {
+    let entry = match tree.get_path(Path::new(path)) {
+        Ok(entry) => entry,
+        Err(err) if err.code() == git2::ErrorCode::NotFound => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+    let blob = entry.to_object(repo)?.peel_to_blob()?;
+    Ok(Some(String::from_utf8_lossy(blob.content()).into_owned()))
+}
+
+/// Reads the staged version of the file, `None` if the file is not in the index
+fn read_index_file(repo: &Repository, path: &str) -> Result<Option<String>> {
+    let index = repo.index()?;
+    let Some(entry) = index.get_path(Path::new(path), 0) else {
+        return Ok(None);
+    };
+    let blob = repo.find_blob(entry.id)?;
+    Ok(Some(String::from_utf8_lossy(blob.content()).into_owned()))
+}
+
+/// Resolves the base and head commits to compare.
+///
+/// The base can be any revspec (branch, tag, SHA, `HEAD~3`, remote ref)
+/// or a `<base>..<head>` / `<base>...<head>` range.
+/// When no head is provided, `None` is returned and the working directory is used instead.
+///
+/// Unless disabled in the configuration, the base is replaced with the merge base
+/// of the base and head (or `HEAD`), which matches `git diff <base>...<head>`.
+///
+/// A `None` base stands for the empty tree, when no commit was made before the `since` date.
+fn resolve_commit_range<'repo>(
+    repo: &'repo Repository,
+    config: &Config,
+) -> Result<(Option<Commit<'repo>>, Option<Commit<'repo>>)> {
+    let head = config.head.as_deref();
+    let remote = config.remote.as_deref().unwrap_or("origin");
+    // freshly fetched remote-tracking branches take precedence over stale local ones
+    let prefer_remote = config.fetch.unwrap_or(false);
 
-    let base_branch = if let Some(main) = base {
-        if repo.find_branch(main, BranchType::Local).is_ok() {
-            main
+    if config.since_tag.is_some() || config.since.is_some() {
+        let head_commit = match head {
+            Some(head) => Some(resolve_revision(repo, head, remote, prefer_remote)?),
+            None => None,
+        };
+        let target = match &head_commit {
+            Some(commit) => commit.clone(),
+            None => repo
+                .head()
+                .and_then(|head| head.peel_to_commit())
+                .context("Could not resolve HEAD commit")?,
+        };
+
+        // both lookups return an ancestor of the head, so there is no need for a merge base
+        let base_commit = if let Some(pattern) = config.since_tag.as_deref() {
+            Some(find_latest_tag(repo, pattern, &target)?)
         } else {
-            bail!("Could not find the specified base branch '{}'", main);
+            let since = config.since.as_deref().unwrap_or_default();
+            find_last_commit_before(repo, since, &target)?
+        };
+
+        return Ok((base_commit, head_commit));
+    }
+
+    let base = match config.base.as_deref() {
+        Some(base) => base,
+        None if resolve_revision(repo, "main", remote, prefer_remote).is_ok() => "main",
+        None if resolve_revision(repo, "master", remote, prefer_remote).is_ok() => "master",
+        None => bail!("Could not find 'main' or 'master' branch"),
+    };
+
+    if base.contains("..") {
+        if head.is_some() {
+            bail!(
+                "Cannot use a head revision together with the '{}' range",
+                base
+            );
+        }
+
+        let spec = repo
+            .revparse(base)
+            .with_context(|| format!("Could not resolve the range '{}'", base))?;
+        let from = spec
+            .from()
+            .with_context(|| format!("Missing base in the range '{}'", base))?
+            .peel_to_commit()?;
+        let to = spec
+            .to()
+            .with_context(|| format!("Missing head in the range '{}'", base))?
+            .peel_to_commit()?;
+
+        let from = if spec.mode().contains(RevparseMode::MERGE_BASE) {
+            let merge_base = repo
+                .merge_base(from.id(), to.id())
+                .with_context(|| format!("Could not find a merge base for '{}'", base))?;
+            debug!("Merge base: {}", merge_base);
+            repo.find_commit(merge_base)?
+        } else {
+            from
+        };
+
+        debug!("Range: {}", base);
+        return Ok((Some(from), Some(to)));
+    }
+
+    let base_commit = resolve_revision(repo, base, remote, prefer_remote)?;
+    let head_commit = match head {
+        Some(head) => Some(resolve_revision(repo, head, remote, prefer_remote)?),
+        None => None,
+    };
+
+    if !config.merge_base.unwrap_or(true) {
+        return Ok((Some(base_commit), head_commit));
+    }
+
+    let target = match &head_commit {
+        Some(commit) => commit.id(),
+        None => repo
+            .head()
+            .and_then(|head| head.peel_to_commit())
+            .context("Could not resolve HEAD commit")?
+            .id(),
+    };
+    let merge_base = repo
+        .merge_base(base_commit.id(), target)
+        .with_context(|| format!("Could not find a merge base between '{}' and HEAD", base))?;
+    debug!("Merge base: {}", merge_base);
+
+    Ok((Some(repo.find_commit(merge_base)?), head_commit))
+}
+
+/// Finds the most recent tag matching the glob pattern that is reachable from `head`.
+fn find_latest_tag<'repo>(
+    repo: &'repo Repository,
+    pattern: &str,
+    head: &Commit,
+) -> Result<Commit<'repo>> {
+    let mut tagged_commits = HashMap::new();
+    for name in repo.tag_names(Some(pattern))?.iter().flatten() {
+        let commit = repo
+            .find_reference(&format!("refs/tags/{}", name))?
+            .peel_to_commit()?;
+        tagged_commits
+            .entry(commit.id())
+            .or_insert_with(|| name.to_string());
+    }
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+    revwalk.push(head.id())?;
+
+    for oid in revwalk {
+        let oid = oid?;
+        if let Some(name) = tagged_commits.get(&oid) {
+            debug!(
+                "Resolved tag pattern '{}' to tag {} ({})",
+                pattern, name, oid
+            );
+            return Ok(repo.find_commit(oid)?);
+        }
+    }
+
+    bail!(
+        "Could not find a tag matching '{}' reachable from HEAD",
+        pattern
+    )
+}
+
+/// Finds the last commit on the first-parent history of `head` made before the given date,
+/// so the changes of all the commits made after the date are reported.
+/// Returns `None` when the whole history was made after the date.
+fn find_last_commit_before<'repo>(
+    repo: &'repo Repository,
+    since: &str,
+    head: &Commit,
+) -> Result<Option<Commit<'repo>>> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs() as i64)
+        .unwrap_or_default();
+    let timestamp = parse_timestamp(since, now)?;
+    debug!("Since timestamp: {}", timestamp);
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.simplify_first_parent()?;
+    revwalk.push(head.id())?;
+
+    for oid in revwalk {
+        let commit = repo.find_commit(oid?)?;
+        if commit.time().seconds() < timestamp {
+            debug!("Resolved '{}' to commit {}", since, commit.id());
+            return Ok(Some(commit));
         }
-    } else if repo.find_branch("main", BranchType::Local).is_ok() {
-        "main"
-    } else if repo.find_branch("master", BranchType::Local).is_ok() {
-        "master"
+    }
+
+    // the missing commits of a shallow clone are fetched before comparing with the empty tree
+    if repo.is_shallow() {
+        bail!("Could not find a commit made before '{}'", since)
+    }
+    debug!(
+        "No commit made before '{}', the whole history is compared",
+        since
+    );
+    Ok(None)
+}
+
+/// Resolves a revision to a commit.
+///
+/// The name is looked up as a local branch, a remote-tracking branch of the given remote,
+/// a tag, and finally as an arbitrary revspec (SHA, `HEAD~3`, `origin/main`, etc.).
+/// With `prefer_remote`, the remote-tracking branch is checked before the local one.
+fn resolve_revision<'repo>(
+    repo: &'repo Repository,
+    name: &str,
+    remote: &str,
+    prefer_remote: bool,
+) -> Result<Commit<'repo>> {
+    let mut candidates = vec![
+        ("local branch", format!("refs/heads/{}", name)),
+        (
+            "remote-tracking branch",
+            format!("refs/remotes/{}/{}", remote, name),
+        ),
+        ("tag", format!("refs/tags/{}", name)),
+    ];
+    if prefer_remote {
+        candidates.swap(0, 1);
+    }
+
+    for (kind, reference_name) in &candidates {
+        if let Ok(reference) = repo.find_reference(reference_name) {
+            debug!("Resolved '{}' to {} {}", name, kind, reference_name);
+            return Ok(reference.peel_to_commit()?);
+        }
+    }
+
+    let commit = repo
+        .revparse_single(name)
+        .and_then(|object| object.peel_to_commit())
+        .with_context(|| format!("Could not resolve the revision '{}'", name))?;
+    debug!("Resolved '{}' to commit {}", name, commit.id());
+
+    Ok(commit)
+}
+
+/// Fetches the base branch from the configured remote.
+///
+/// Branches are fetched into `refs/remotes/<remote>/<name>`, tags into `refs/tags/<name>`
+/// and full SHAs by object id, while ranges and other revisions fall back to the remote's default refspecs.
+/// Tags are fetched as well, so tag-based bases stay up to date.
+///
+/// With a `depth`, the current branch is fetched too and the history is limited
+/// (or extended, for shallow clones) to the given number of commits.
+fn fetch_base(repo: &Repository, config: &Config, depth: Option<i32>) -> Result<()> {
+    let remote_name = config.remote.as_deref().unwrap_or("origin");
+    let remote = repo
+        .find_remote(remote_name)
+        .with_context(|| format!("Could not find remote '{}'", remote_name))?;
+    let url = remote.url().unwrap_or_default().to_string();
+    let default_refspecs: Vec<String> = remote
+        .fetch_refspecs()?
+        .iter()
+        .flatten()
+        .map(str::to_string)
+        .collect();
+
+    // relative remote paths are resolved against the working directory like git does,
+    // libgit2 resolves them against the current directory
+    let workdir = repo.workdir().unwrap_or_else(|| repo.path());
+    let local_path = workdir.join(&url);
+    let is_local = url.starts_with("file://") || local_path.is_dir();
+    let mut remote = if is_local && Path::new(&url).is_relative() && !url.starts_with("file://") {
+        repo.remote_anonymous(&local_path.to_string_lossy())?
     } else {
-        bail!("Could not find 'main' or 'master' branch");
+        remote
     };
-    debug!("Base branch: {}", base_branch);
 
-    let main_ref = format!("refs/heads/{}", base_branch);
-    debug!("Base ref: {}", main_ref);
+    let base = config.base.as_deref().unwrap_or("main");
+    let mut refspecs = base_refspecs(repo, &mut remote, remote_name, base)?;
+
+    let mut fetch_opts = FetchOptions::new();
+    fetch_opts.remote_callbacks(remote_callbacks(repo));
+    fetch_opts.download_tags(AutotagOption::All);
 
-    let main_oid = repo
-        .revparse_single(&main_ref)
-        .context("Could not find the base branch OID")?
-        .id();
+    if let Some(depth) = depth {
+        fetch_opts.depth(depth);
+
+        if !refspecs.is_empty() {
+            if let Ok(head) = repo.head() {
+                if let Some(current_branch) = head.shorthand().filter(|_| head.is_branch()) {
+                    refspecs.push(format!(
+                        "+refs/heads/{0}:refs/remotes/{1}/{0}",
+                        current_branch, remote_name
+                    ));
+                }
+            }
+        }
+    }
 
-    debug!("Base OID: {}", main_oid);
+    debug!(
+        "Fetching {:?} from remote '{}' ({})",
+        refspecs, remote_name, url
+    );
 
-    // Get the trees for each branch's commit
-    // let current_tree = repo.find_commit(current_oid)?.tree()?;
-    let base_tree = repo.find_commit(main_oid)?.tree()?;
+    // libgit2 does not support shallow fetches or shallow clones over the local transport
+    if is_local && (depth.is_some() || repo.is_shallow()) {
+        return fetch_with_git_cli(repo, remote_name, &refspecs, depth);
+    }
 
-    // Compare the trees to get the diff
-    let mut diff_opts = DiffOptions::new();
-    // let diff =
-    //     repo.diff_tree_to_tree(Some(&base_tree), Some(&current_tree), Some(&mut diff_opts))?;
-    let diff = repo.diff_tree_to_workdir_with_index(Some(&base_tree), Some(&mut diff_opts))?;
+    // the anonymous remotes have no default refspecs
+    if refspecs.is_empty() {
+        refspecs = default_refspecs;
+    }
+    remote
+        .fetch(&refspecs, Some(&mut fetch_opts), None)
+        .with_context(|| format!("Failed to fetch from remote '{}'", remote_name))?;
 
-    let mut result = HashSet::new();
+    Ok(())
+}
+
+/// Returns the refspecs fetching the base, empty for the remote's default refspecs
+fn base_refspecs(
+    repo: &Repository,
+    remote: &mut Remote,
+    remote_name: &str,
+    base: &str,
+) -> Result<Vec<String>> {
+    // ranges and revisions like `HEAD~3` or `main^` cannot be fetched directly
+    if base.contains("..") || base.contains(['~', '^', ':']) || base.contains("@{") {
+        return Ok(vec![]);
+    }
+    // full SHAs are fetched by object id, short ones are not supported by the remotes
+    if base.chars().all(|c| c.is_ascii_hexdigit()) && base.len() >= 7 {
+        return Ok(if matches!(base.len(), 40 | 64) {
+            vec![base.to_string()]
+        } else {
+            vec![]
+        });
+    }
+
+    let name = base
+        .strip_prefix(&format!("{}/", remote_name))
+        .unwrap_or(base);
+    let tag = name.strip_prefix("refs/tags/").unwrap_or(name);
+    let branch = name.strip_prefix("refs/heads/").unwrap_or(name);
+
+    let connection = remote
+        .connect_auth(Direction::Fetch, Some(remote_callbacks(repo)), None)
+        .with_context(|| format!("Could not connect to remote '{}'", remote_name))?;
+    let remote_refs: HashSet<String> = connection
+        .list()?
+        .iter()
+        .map(|head| head.name().to_string())
+        .collect();
+
+    let refspecs = if remote_refs.contains(&format!("refs/heads/{}", branch)) {
+        vec![format!(
+            "+refs/heads/{0}:refs/remotes/{1}/{0}",
+            branch, remote_name
+        )]
+    } else if remote_refs.contains(&format!("refs/tags/{}", tag)) {
+        vec![format!("refs/tags/{}", tag)]
+    } else {
+        debug!(
+            "'{}' is neither a branch nor a tag of '{}'",
+            base, remote_name
+        );
+        vec![]
+    };
+    Ok(refspecs)
+}
 
-    for delta in diff.deltas() {
-        if let Some(path) = delta.new_file().path() {
-            result.insert(path.to_string_lossy().to_string());
+/// Fetches from a local remote using the git command line, which supports shallow fetches.
+fn fetch_with_git_cli(
+    repo: &Repository,
+    remote_name: &str,
+    refspecs: &[String],
+    depth: Option<i32>,
+) -> Result<()> {
+    let workdir = repo.workdir().unwrap_or_else(|| repo.path());
+
+    let mut command = std::process::Command::new("git");
+    command.arg("-C").arg(workdir).arg("fetch").arg("--quiet");
+    match depth {
+        Some(UNSHALLOW_DEPTH) => {
+            command.arg("--unshallow");
+        }
+        Some(depth) => {
+            command.arg(format!("--depth={}", depth));
         }
+        None => {}
+    }
+    // unlike libgit2 with `AutotagOption::All`, git stores the fetched tags only with a destination
+    command
+        .arg(remote_name)
+        .args(refspecs.iter().map(|refspec| {
+            if refspec.starts_with("refs/tags/") {
+                format!("+{0}:{0}", refspec)
+            } else {
+                refspec.clone()
+            }
+        }));
+
+    debug!("Running {:?}", command);
+    let status = command.status().context("Failed to run git")?;
+    if !status.success() {
+        bail!("Failed to fetch from remote '{}'", remote_name);
     }
 
-    Ok(result)
+    Ok(())
+}
+
+/// Fetches more history into a shallow clone until the base and the merge base are reachable.
+///
+/// The depth is doubled on every attempt, and the full history is fetched as a last resort.
+/// Returns the reopened repository, as libgit2 caches the shallow boundaries.
+fn deepen_history(mut repo: Repository, config: &Config) -> Result<Repository> {
+    const MAX_DEPTH: i32 = 3200;
+
+    let mut depth = 50;
+
+    loop {
+        let err = match resolve_commit_range(&repo, config).map(|_| ()) {
+            Ok(()) => return Ok(repo),
+            Err(err) => err,
+        };
+
+        if !repo.is_shallow() {
+            return Err(err.context("Could not find the base commit after deepening the history"));
+        }
+        debug!("{:#}", err);
+
+        if depth > MAX_DEPTH {
+            depth = UNSHALLOW_DEPTH;
+            debug!("Fetching the complete history");
+        } else {
+            debug!("Deepening the history to {} commits", depth);
+        }
+
+        fetch_base(&repo, config, Some(depth))?;
+        repo = Repository::open(repo.path()).context("Could not open the repository")?;
+
+        if depth == UNSHALLOW_DEPTH {
+            resolve_commit_range(&repo, config)
+                .context("Could not find the base commit after fetching the complete history")?;
+            return Ok(repo);
+        }
+
+        depth *= 2;
+    }
+}
+
+/// Creates remote callbacks that authenticate via the SSH agent or git credential helpers.
+fn remote_callbacks(repo: &Repository) -> RemoteCallbacks<'_> {
+    let mut callbacks = RemoteCallbacks::new();
+    let mut attempts = 0;
+
+    callbacks.credentials(move |url, username, allowed| {
+        attempts += 1;
+        if attempts > 3 {
+            return Err(git2::Error::from_str("Authentication failed"));
+        }
+
+        if allowed.contains(CredentialType::SSH_KEY) {
+            Cred::ssh_key_from_agent(username.unwrap_or("git"))
+        } else if allowed.contains(CredentialType::USER_PASS_PLAINTEXT) {
+            Cred::credential_helper(&repo.config()?, url, username)
+        } else {
+            Cred::default()
+        }
+    });
+
+    callbacks
+}
+
+/// Finds the project owning the file, which is the most nested project containing it.
+fn find_owning_project<'a>(graph: &'a WorkspaceGraph, path: &str) -> Option<&'a ProjectNode> {
+    graph
+        .node_weights()
+        .filter_map(|node| match node {
+            NodeType::Project(project) if Path::new(path).starts_with(&project.root) => {
+                Some(project)
+            }
+            _ => None,
+        })
+        .max_by_key(|project| Path::new(&project.root).components().count())
 }
 
-fn inspect_workspace<F>(workspace_root: &PathBuf, filter_fn: F) -> Result<HashSet<String>>
+fn inspect_workspace<F>(workspace_root: &PathBuf, mut filter_fn: F) -> Result<HashSet<String>>
 where
-    F: Fn(&Path) -> bool,
+    F: FnMut(&Path) -> bool,
 {
     let walker = WalkBuilder::new(workspace_root)
         .follow_links(true)
         .standard_filters(true) // Respect .gitignore, .ignore, etc.
+        .filter_entry(|entry| entry.file_name() != "node_modules")
         .build();
 
     let mut paths = HashSet::new();
@@ -295,3 +1614,282 @@ where
 
     Ok(paths)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::build::CheckoutBuilder;
+    use git2::{BranchType, IndexAddOption, RepositoryInitOptions, Signature};
+    use std::fs;
+    use std::process::Command;
+
+    fn write(root: &Path, path: &str, contents: &str) {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    /// Creates a repository on `main` with the `a`, `b` and `c` projects
+    fn init(root: &Path) -> Repository {
+        let mut opts = RepositoryInitOptions::new();
+        opts.initial_head("main");
+        let repo = Repository::init_opts(root, &opts).unwrap();
+        for name in ["a", "b", "c"] {
+            write(
+                root,
+                &format!("libs/{}/package.json", name),
+                &format!(r#"{{ "name": "{}" }}"#, name),
+            );
+            write(root, &format!("libs/{}/index.js", name), "export {};\n");
+        }
+        commit(&repo, "initial");
+        repo
+    }
+
+    fn stage(repo: &Repository) {
+        let mut index = repo.index().unwrap();
+        index.add_all(["*"], IndexAddOption::DEFAULT, None).unwrap();
+        index.update_all(["*"], None).unwrap();
+        index.write().unwrap();
+    }
+
+    fn commit(repo: &Repository, message: &str) -> Oid {
+        stage(repo);
+        let tree = repo
+            .find_tree(repo.index().unwrap().write_tree().unwrap())
+            .unwrap();
+        let signature = Signature::now("Test", "test@example.com").unwrap();
+        let parent = repo.head().and_then(|head| head.peel_to_commit()).ok();
+        let parents: Vec<&Commit> = parent.iter().collect();
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &parents,
+        )
+        .unwrap()
+    }
+
+    fn branch(repo: &Repository, name: &str) {
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch(name, &head, false).unwrap();
+    }
+
+    fn checkout(repo: &Repository, name: &str) {
+        repo.set_head(&format!("refs/heads/{}", name)).unwrap();
+        repo.checkout_head(Some(CheckoutBuilder::new().force()))
+            .unwrap();
+    }
+
+    /// Changes `b` on `main` and `a` on `feature`, both branching off the initial commit
+    fn diverge(root: &Path) -> Repository {
+        let repo = init(root);
+        branch(&repo, "feature");
+        write(root, "libs/b/index.js", "export const b = 1;\n");
+        commit(&repo, "change b");
+        checkout(&repo, "feature");
+        write(root, "libs/a/index.js", "export const a = 1;\n");
+        commit(&repo, "change a");
+        repo
+    }
+
+    fn config(base: &str) -> Config {
+        Config {
+            base: Some(base.to_string()),
+            ..Default::default()
+        }
+    }
+
+    async fn load(root: &Path, config: Config) -> Result<Workspace> {
+        let mut workspace = Workspace::with_config(root, config);
+        workspace.load().await?;
+        Ok(workspace)
+    }
+
+    fn sorted(projects: HashSet<String>) -> Vec<String> {
+        let mut projects: Vec<String> = projects.into_iter().collect();
+        projects.sort();
+        projects
+    }
+
+    async fn affected_projects(root: &Path, config: Config) -> Vec<String> {
+        let workspace = load(root, config).await.unwrap();
+        sorted(workspace.affected_projects().unwrap())
+    }
+
+    #[tokio::test]
+    async fn compares_with_the_merge_base() {
+        let dir = tempfile::tempdir().unwrap();
+        diverge(dir.path());
+
+        assert_eq!(affected_projects(dir.path(), config("main")).await, ["a"]);
+
+        let config = Config {
+            merge_base: Some(false),
+            ..config("main")
+        };
+        assert_eq!(affected_projects(dir.path(), config).await, ["a", "b"]);
+    }
+
+    #[tokio::test]
+    async fn resolves_ranges() {
+        let dir = tempfile::tempdir().unwrap();
+        diverge(dir.path());
+        checkout(&Repository::open(dir.path()).unwrap(), "main");
+
+        assert_eq!(
+            affected_projects(dir.path(), config("main..feature")).await,
+            ["a", "b"]
+        );
+        assert_eq!(
+            affected_projects(dir.path(), config("main...feature")).await,
+            ["a"]
+        );
+
+        let config = Config {
+            head: Some("feature".to_string()),
+            ..config("main..feature")
+        };
+        assert!(load(dir.path(), config).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn falls_back_to_the_remote_tracking_branch() {
+        let upstream = tempfile::tempdir().unwrap();
+        diverge(upstream.path());
+        let clone = tempfile::tempdir().unwrap();
+        let url = format!("file://{}", upstream.path().display());
+        let repo = Repository::clone(&url, clone.path()).unwrap();
+        assert!(repo.find_branch("main", BranchType::Local).is_err());
+
+        assert_eq!(affected_projects(clone.path(), config("main")).await, ["a"]);
+    }
+
+    #[test]
+    fn resolves_revisions_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = init(dir.path());
+        let initial = repo.head().unwrap().peel_to_commit().unwrap().id();
+        write(dir.path(), "libs/a/index.js", "export const a = 1;\n");
+        let latest = commit(&repo, "change a");
+
+        repo.reference("refs/remotes/origin/develop", latest, false, "test")
+            .unwrap();
+        repo.reference("refs/remotes/upstream/develop", initial, false, "test")
+            .unwrap();
+        repo.tag_lightweight("v1", &repo.find_object(initial, None).unwrap(), false)
+            .unwrap();
+
+        let resolve = |name: &str, remote: &str, prefer_remote: bool| {
+            resolve_revision(&repo, name, remote, prefer_remote)
+                .map(|commit| commit.id())
+                .ok()
+        };
+
+        assert_eq!(resolve("develop", "origin", false), Some(latest));
+        assert_eq!(resolve("develop", "upstream", false), Some(initial));
+        assert_eq!(resolve("v1", "origin", false), Some(initial));
+        assert_eq!(resolve("HEAD~1", "origin", false), Some(initial));
+        assert_eq!(resolve("missing", "origin", false), None);
+
+        // the local branch wins unless the remote-tracking one was just fetched
+        repo.reference("refs/heads/develop", initial, false, "test")
+            .unwrap();
+        assert_eq!(resolve("develop", "origin", false), Some(initial));
+        assert_eq!(resolve("develop", "origin", true), Some(latest));
+    }
+
+    #[tokio::test]
+    async fn renames_affect_both_projects() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = init(dir.path());
+        let contents = "export function format(value) {\n  return String(value);\n}\n";
+        write(dir.path(), "libs/a/format.js", contents);
+        commit(&repo, "add format");
+        fs::remove_file(dir.path().join("libs/a/format.js")).unwrap();
+        write(dir.path(), "libs/b/format.js", contents);
+        commit(&repo, "move format");
+
+        let workspace = load(dir.path(), config("HEAD~1")).await.unwrap();
+        assert_eq!(sorted(workspace.affected_projects().unwrap()), ["a", "b"]);
+
+        let files = workspace.affected_file_changes().unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, "libs/b/format.js");
+        assert_eq!(files[0].old_path.as_deref(), Some("libs/a/format.js"));
+
+        let commits = workspace.affected_commits().unwrap();
+        assert_eq!(commits.len(), 1);
+        assert_eq!(commits[0].summary, "move format");
+        assert_eq!(commits[0].files, ["libs/a/format.js", "libs/b/format.js"]);
+        assert_eq!(commits[0].projects, ["a", "b"]);
+    }
+
+    #[tokio::test]
+    async fn separates_the_diff_modes() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = init(dir.path());
+        write(dir.path(), "libs/a/index.js", "export const a = 1;\n");
+        commit(&repo, "change a");
+        write(dir.path(), "libs/b/index.js", "export const b = 1;\n");
+        stage(&repo);
+        write(dir.path(), "libs/c/index.js", "export const c = 1;\n");
+
+        let workspace = load(dir.path(), config("HEAD~1")).await.unwrap();
+        let projects = |mode| sorted(workspace.affected_projects_for_mode(mode).unwrap());
+
+        assert_eq!(projects(DiffMode::Committed), ["a"]);
+        assert_eq!(projects(DiffMode::Staged), ["a", "b"]);
+        assert_eq!(projects(DiffMode::Unstaged), ["c"]);
+        assert_eq!(projects(DiffMode::All), ["a", "b", "c"]);
+        assert_eq!(
+            sorted(workspace.affected_projects().unwrap()),
+            ["a", "b", "c"]
+        );
+
+        let config = Config {
+            head: Some("HEAD".to_string()),
+            mode: Some(DiffMode::Staged),
+            ..config("HEAD~1")
+        };
+        assert!(load(dir.path(), config).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn fetches_the_base_into_shallow_clones() {
+        let upstream = tempfile::tempdir().unwrap();
+        let repo = init(upstream.path());
+        branch(&repo, "develop");
+        for value in 1..=3 {
+            write(
+                upstream.path(),
+                "libs/a/index.js",
+                &format!("export const a = {};\n", value),
+            );
+            commit(&repo, &format!("change a {}", value));
+        }
+
+        let clone = tempfile::tempdir().unwrap();
+        let status = Command::new("git")
+            .args(["clone", "--quiet", "--depth=1"])
+            .arg(format!("file://{}", upstream.path().display()))
+            .arg(clone.path())
+            .status()
+            .unwrap();
+        assert!(status.success());
+        assert!(Repository::open(clone.path()).unwrap().is_shallow());
+
+        let err = load(clone.path(), config("develop")).await.err().unwrap();
+        assert!(format!("{:#}", err).contains("shallow clone"));
+
+        let config = Config {
+            fetch: Some(true),
+            ..config("develop")
+        };
+        let workspace = load(clone.path(), config).await.unwrap();
+        assert_eq!(sorted(workspace.affected_projects().unwrap()), ["a"]);
+        assert_eq!(workspace.affected_commits().unwrap().len(), 3);
+    }
+}